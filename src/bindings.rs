@@ -0,0 +1,718 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_console::ConsoleOpen;
+use serde::{Deserialize, Serialize};
+
+use crate::hud::{HudPanel, RightColumn};
+
+const BINDINGS_DIRECTORY: &str = "./saves";
+const BINDINGS_PATH: &str = "./saves/bindings.json";
+
+/// Opens the rebinding screen, fixed so it can never be bound away
+pub const BINDINGS_KEY: KeyCode = KeyCode::F1;
+const CANCEL_KEY: KeyCode = KeyCode::Escape;
+const CLEAR_KEY: KeyCode = KeyCode::Delete;
+const DEFAULT_KEY: KeyCode = KeyCode::Home;
+
+/// How far a stick must be pushed to count as pressed
+const AXIS_THRESHOLD: f32 = 0.5;
+const STICKS: [GamepadAxis; 4] = [
+    GamepadAxis::LeftStickX,
+    GamepadAxis::LeftStickY,
+    GamepadAxis::RightStickX,
+    GamepadAxis::RightStickY,
+];
+const MODIFIER_KEYS: [KeyCode; 2] = [KeyCode::ControlLeft, KeyCode::ControlRight];
+
+pub struct BindingsPlugin;
+
+impl Plugin for BindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Bindings::load())
+            .insert_resource(BindingScreen::default());
+
+        app.add_systems(Startup, setup.in_set(HudPanel::Bindings))
+            .add_systems(
+                Update,
+                (
+                    (toggle_binding_screen, binding_screen_keys).chain(),
+                    update_binding_display,
+                ),
+            );
+    }
+}
+
+/// Things the player can do, each bound to any number of inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Action {
+    PanForward,
+    PanBack,
+    PanLeft,
+    PanRight,
+    /// turns the camera while held and the mouse moves
+    Rotate,
+    RotateLeft,
+    RotateRight,
+    CursorUp,
+    CursorDown,
+    CursorLeft,
+    CursorRight,
+    /// puts the grid cursor on the map, or takes it off
+    ToggleCursor,
+    /// uses the tool on the cursor's cell, as a left click would
+    UseTool,
+    /// does the tool's opposite on the cursor's cell, as a right click would
+    UseOpposite,
+    NextTool,
+    PreviousTool,
+    Undo,
+    ResetLevel,
+    /// switches between playing and editing the level
+    ToggleEditor,
+    LevelSelect,
+    /// the numbered slots pick a tool, an editor tool or a level, whichever is showing
+    Slot1,
+    Slot2,
+    Slot3,
+    Slot4,
+    Slot5,
+    Slot6,
+    Slot7,
+    Slot8,
+    Slot9,
+    Slot10,
+    CopyTool,
+    PasteTool,
+    BrushSmaller,
+    BrushLarger,
+    BrushWeaker,
+    BrushStronger,
+    BrushShape,
+    TurnStamp,
+    MirrorStamp,
+    BlendStamp,
+    RaiseSelection,
+    LowerSelection,
+    LevelSelection,
+    FillSelection,
+    LockSelection,
+    /// held while dragging a box to add it to the selection
+    SelectAdd,
+    /// held while dragging a box to flip each cell in it
+    SelectToggle,
+    /// drops a half-finished path, copy or selection
+    Cancel,
+    /// the soil the editor paints with
+    NextSoil,
+    GrowMap,
+    ShrinkMap,
+}
+
+impl Action {
+    pub const ALL: [Action; 51] = [
+        Action::PanForward,
+        Action::PanBack,
+        Action::PanLeft,
+        Action::PanRight,
+        Action::Rotate,
+        Action::RotateLeft,
+        Action::RotateRight,
+        Action::CursorUp,
+        Action::CursorDown,
+        Action::CursorLeft,
+        Action::CursorRight,
+        Action::ToggleCursor,
+        Action::UseTool,
+        Action::UseOpposite,
+        Action::NextTool,
+        Action::PreviousTool,
+        Action::Undo,
+        Action::ResetLevel,
+        Action::ToggleEditor,
+        Action::LevelSelect,
+        Action::Slot1,
+        Action::Slot2,
+        Action::Slot3,
+        Action::Slot4,
+        Action::Slot5,
+        Action::Slot6,
+        Action::Slot7,
+        Action::Slot8,
+        Action::Slot9,
+        Action::Slot10,
+        Action::CopyTool,
+        Action::PasteTool,
+        Action::BrushSmaller,
+        Action::BrushLarger,
+        Action::BrushWeaker,
+        Action::BrushStronger,
+        Action::BrushShape,
+        Action::TurnStamp,
+        Action::MirrorStamp,
+        Action::BlendStamp,
+        Action::RaiseSelection,
+        Action::LowerSelection,
+        Action::LevelSelection,
+        Action::FillSelection,
+        Action::LockSelection,
+        Action::SelectAdd,
+        Action::SelectToggle,
+        Action::Cancel,
+        Action::NextSoil,
+        Action::GrowMap,
+        Action::ShrinkMap,
+    ];
+
+    /// The numbered slots, in order
+    pub const SLOTS: [Action; 10] = [
+        Action::Slot1,
+        Action::Slot2,
+        Action::Slot3,
+        Action::Slot4,
+        Action::Slot5,
+        Action::Slot6,
+        Action::Slot7,
+        Action::Slot8,
+        Action::Slot9,
+        Action::Slot10,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::PanForward => "pan-forward",
+            Action::PanBack => "pan-back",
+            Action::PanLeft => "pan-left",
+            Action::PanRight => "pan-right",
+            Action::Rotate => "rotate",
+            Action::RotateLeft => "rotate-left",
+            Action::RotateRight => "rotate-right",
+            Action::CursorUp => "cursor-up",
+            Action::CursorDown => "cursor-down",
+            Action::CursorLeft => "cursor-left",
+            Action::CursorRight => "cursor-right",
+            Action::ToggleCursor => "toggle-cursor",
+            Action::UseTool => "use-tool",
+            Action::UseOpposite => "use-opposite",
+            Action::NextTool => "next-tool",
+            Action::PreviousTool => "previous-tool",
+            Action::Undo => "undo",
+            Action::ResetLevel => "reset-level",
+            Action::ToggleEditor => "toggle-editor",
+            Action::LevelSelect => "level-select",
+            Action::Slot1 => "slot-1",
+            Action::Slot2 => "slot-2",
+            Action::Slot3 => "slot-3",
+            Action::Slot4 => "slot-4",
+            Action::Slot5 => "slot-5",
+            Action::Slot6 => "slot-6",
+            Action::Slot7 => "slot-7",
+            Action::Slot8 => "slot-8",
+            Action::Slot9 => "slot-9",
+            Action::Slot10 => "slot-10",
+            Action::CopyTool => "copy-tool",
+            Action::PasteTool => "paste-tool",
+            Action::BrushSmaller => "brush-smaller",
+            Action::BrushLarger => "brush-larger",
+            Action::BrushWeaker => "brush-weaker",
+            Action::BrushStronger => "brush-stronger",
+            Action::BrushShape => "brush-shape",
+            Action::TurnStamp => "turn-stamp",
+            Action::MirrorStamp => "mirror-stamp",
+            Action::BlendStamp => "blend-stamp",
+            Action::RaiseSelection => "raise-selection",
+            Action::LowerSelection => "lower-selection",
+            Action::LevelSelection => "level-selection",
+            Action::FillSelection => "fill-selection",
+            Action::LockSelection => "lock-selection",
+            Action::SelectAdd => "select-add",
+            Action::SelectToggle => "select-toggle",
+            Action::Cancel => "cancel",
+            Action::NextSoil => "next-soil",
+            Action::GrowMap => "grow-map",
+            Action::ShrinkMap => "shrink-map",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// The inputs used when no bindings file says otherwise
+    pub fn default_bindings(&self) -> Vec<Binding> {
+        use Binding::*;
+
+        let stick = |axis, positive| Axis { axis, positive };
+        match self {
+            //  the arrow keys pan too, until the grid cursor is put on the map
+            Action::PanForward => vec![
+                Key(KeyCode::KeyW),
+                Key(KeyCode::ArrowUp),
+                stick(GamepadAxis::LeftStickY, true),
+            ],
+            Action::PanBack => vec![
+                Key(KeyCode::KeyS),
+                Key(KeyCode::ArrowDown),
+                stick(GamepadAxis::LeftStickY, false),
+            ],
+            Action::PanLeft => vec![
+                Key(KeyCode::KeyA),
+                Key(KeyCode::ArrowLeft),
+                stick(GamepadAxis::LeftStickX, false),
+            ],
+            Action::PanRight => vec![
+                Key(KeyCode::KeyD),
+                Key(KeyCode::ArrowRight),
+                stick(GamepadAxis::LeftStickX, true),
+            ],
+            Action::Rotate => vec![Mouse(MouseButton::Middle)],
+            Action::RotateLeft => vec![stick(GamepadAxis::RightStickX, false)],
+            Action::RotateRight => vec![stick(GamepadAxis::RightStickX, true)],
+            Action::CursorUp => vec![Key(KeyCode::ArrowUp), Gamepad(GamepadButton::DPadUp)],
+            Action::CursorDown => vec![Key(KeyCode::ArrowDown), Gamepad(GamepadButton::DPadDown)],
+            Action::CursorLeft => vec![Key(KeyCode::ArrowLeft), Gamepad(GamepadButton::DPadLeft)],
+            Action::CursorRight => {
+                vec![Key(KeyCode::ArrowRight), Gamepad(GamepadButton::DPadRight)]
+            }
+            Action::ToggleCursor => vec![Key(KeyCode::KeyX)],
+            Action::UseTool => vec![
+                Mouse(MouseButton::Left),
+                Key(KeyCode::Enter),
+                Gamepad(GamepadButton::South),
+            ],
+            Action::UseOpposite => vec![
+                Mouse(MouseButton::Right),
+                Key(KeyCode::Backspace),
+                Gamepad(GamepadButton::East),
+            ],
+            Action::NextTool => vec![Key(KeyCode::Tab), Gamepad(GamepadButton::RightTrigger)],
+            Action::PreviousTool => vec![Gamepad(GamepadButton::LeftTrigger)],
+            Action::Undo => vec![CtrlKey(KeyCode::KeyZ), Gamepad(GamepadButton::North)],
+            Action::ResetLevel => vec![Key(KeyCode::KeyR), Gamepad(GamepadButton::Select)],
+            Action::ToggleEditor => vec![Key(KeyCode::KeyE)],
+            Action::LevelSelect => vec![Key(KeyCode::KeyL)],
+            Action::Slot1 => vec![Key(KeyCode::Digit1)],
+            Action::Slot2 => vec![Key(KeyCode::Digit2)],
+            Action::Slot3 => vec![Key(KeyCode::Digit3)],
+            Action::Slot4 => vec![Key(KeyCode::Digit4)],
+            Action::Slot5 => vec![Key(KeyCode::Digit5)],
+            Action::Slot6 => vec![Key(KeyCode::Digit6)],
+            Action::Slot7 => vec![Key(KeyCode::Digit7)],
+            Action::Slot8 => vec![Key(KeyCode::Digit8)],
+            Action::Slot9 => vec![Key(KeyCode::Digit9)],
+            Action::Slot10 => vec![Key(KeyCode::Digit0)],
+            Action::CopyTool => vec![Key(KeyCode::KeyC)],
+            Action::PasteTool => vec![Key(KeyCode::KeyV)],
+            Action::BrushSmaller => vec![Key(KeyCode::BracketLeft)],
+            Action::BrushLarger => vec![Key(KeyCode::BracketRight)],
+            Action::BrushWeaker => vec![Key(KeyCode::Comma)],
+            Action::BrushStronger => vec![Key(KeyCode::Period)],
+            Action::BrushShape => vec![Key(KeyCode::KeyB)],
+            Action::TurnStamp => vec![Key(KeyCode::KeyT)],
+            Action::MirrorStamp => vec![Key(KeyCode::KeyM)],
+            Action::BlendStamp => vec![Key(KeyCode::KeyG)],
+            Action::RaiseSelection => vec![Key(KeyCode::PageUp)],
+            Action::LowerSelection => vec![Key(KeyCode::PageDown)],
+            Action::LevelSelection => vec![Key(KeyCode::KeyH)],
+            Action::FillSelection => vec![Key(KeyCode::KeyF)],
+            Action::LockSelection => vec![Key(KeyCode::KeyK)],
+            Action::SelectAdd => vec![
+                Key(KeyCode::ShiftLeft),
+                Key(KeyCode::ShiftRight),
+                Gamepad(GamepadButton::LeftTrigger2),
+            ],
+            Action::SelectToggle => vec![
+                Key(KeyCode::ControlLeft),
+                Key(KeyCode::ControlRight),
+                Gamepad(GamepadButton::RightTrigger2),
+            ],
+            Action::Cancel => vec![Key(KeyCode::Escape)],
+            Action::NextSoil => vec![Key(KeyCode::KeyQ)],
+            Action::GrowMap => vec![Key(KeyCode::Equal)],
+            Action::ShrinkMap => vec![Key(KeyCode::Minus)],
+        }
+    }
+}
+
+/// One input that can trigger an action
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    /// a key pressed while Ctrl is held
+    CtrlKey(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+    /// a stick pushed past halfway, which only ever counts as held and never as just pressed
+    Axis {
+        axis: GamepadAxis,
+        positive: bool,
+    },
+}
+
+impl Binding {
+    /// A short name, as written to and read from the console
+    pub fn name(&self) -> String {
+        match self {
+            Binding::Key(key) => format!("key:{:?}", key),
+            Binding::CtrlKey(key) => format!("ctrl:{:?}", key),
+            Binding::Mouse(button) => format!("mouse:{:?}", button),
+            Binding::Gamepad(button) => format!("pad:{:?}", button),
+            Binding::Axis { axis, positive } => {
+                format!("axis:{:?}{}", axis, if *positive { "+" } else { "-" })
+            }
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        //  the input names are the variant names bevy serializes them as
+        fn input<T: for<'de> Deserialize<'de>>(name: &str) -> Option<T> {
+            serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+        }
+
+        let (kind, input_name) = name.split_once(':')?;
+        match kind {
+            "key" => input(input_name).map(Binding::Key),
+            "ctrl" => input(input_name).map(Binding::CtrlKey),
+            "mouse" => input(input_name).map(Binding::Mouse),
+            "pad" => input(input_name).map(Binding::Gamepad),
+            "axis" => {
+                let (axis, positive) = match input_name.strip_suffix('+') {
+                    Some(axis) => (axis, true),
+                    None => (input_name.strip_suffix('-')?, false),
+                };
+                Some(Binding::Axis {
+                    axis: input(axis)?,
+                    positive,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_gamepad(&self) -> bool {
+        matches!(self, Binding::Gamepad(_) | Binding::Axis { .. })
+    }
+}
+
+/// The inputs bound to each action, persisted to the bindings file
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct Bindings(pub BTreeMap<Action, Vec<Binding>>);
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self(
+            Action::ALL
+                .into_iter()
+                .map(|action| (action, action.default_bindings()))
+                .collect(),
+        )
+    }
+}
+
+impl Bindings {
+    fn load() -> Self {
+        let Ok(json_data) = fs::read_to_string(BINDINGS_PATH) else {
+            //  no bindings file yet
+            return Self::default();
+        };
+
+        match serde_json::from_str::<Bindings>(&json_data) {
+            Err(e) => {
+                warn!("failed to deserialize bindings: {:?}", e);
+                Self::default()
+            }
+            Ok(loaded) => {
+                //  actions added since the file was written keep their defaults
+                let mut bindings = Self::default();
+                bindings.0.extend(loaded.0);
+                bindings
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let json_data = match serde_json::to_string_pretty(self) {
+            Err(e) => {
+                warn!("failed to serialize bindings: {:?}", e);
+                return;
+            }
+            Ok(data) => data,
+        };
+
+        if let Err(e) = fs::create_dir_all(Path::new(BINDINGS_DIRECTORY))
+            .and_then(|_| fs::write(BINDINGS_PATH, json_data))
+        {
+            warn!("failed to write bindings: {:?}", e);
+        }
+    }
+
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.0.get(&action).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// The action's bindings as a list of names
+    pub fn describe(&self, action: Action) -> String {
+        let names: Vec<String> = self.get(action).iter().map(Binding::name).collect();
+        if names.is_empty() {
+            String::from("unbound")
+        } else {
+            names.join(", ")
+        }
+    }
+
+    /// The first key bound to the action, for labels such as the toolbar's
+    pub fn key(&self, action: Action) -> Option<KeyCode> {
+        self.get(action).iter().find_map(|binding| match binding {
+            Binding::Key(key) => Some(*key),
+            _ => None,
+        })
+    }
+
+    /// Binds a new input, replacing the action's inputs on the same device
+    fn rebind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.0.entry(action).or_default();
+        bindings.retain(|bound| bound.is_gamepad() != binding.is_gamepad());
+        bindings.push(binding);
+    }
+}
+
+/// Reads actions through their bindings, so systems never name keys or buttons
+#[derive(SystemParam)]
+pub struct ActionInput<'w, 's> {
+    bindings: Res<'w, Bindings>,
+    screen: Res<'w, BindingScreen>,
+    console: Res<'w, ConsoleOpen>,
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl ActionInput<'_, '_> {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.active()
+            && self
+                .bindings
+                .get(action)
+                .iter()
+                .any(|binding| self.binding_pressed(binding))
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.active()
+            && self
+                .bindings
+                .get(action)
+                .iter()
+                .any(|binding| self.binding_just_pressed(binding))
+    }
+
+    /// The first of the actions the mouse button is bound to, for clicks the pointer reports
+    pub fn mouse_action(&self, button: MouseButton, actions: &[Action]) -> Option<Action> {
+        //  observers can run long after the screen last changed, so only being open counts
+        if self.screen.open || self.console.open {
+            return None;
+        }
+        actions
+            .iter()
+            .copied()
+            .find(|action| self.bindings.get(*action).contains(&Binding::Mouse(button)))
+    }
+
+    /// Nothing reaches the game while the rebinding screen or the console is open, nor on the
+    /// frame either changes, so the press that closes it or is captured by it is not also an action
+    fn active(&self) -> bool {
+        !self.screen.open
+            && !self.screen.is_changed()
+            && !self.console.open
+            && !self.console.is_changed()
+    }
+
+    fn control(&self) -> bool {
+        self.keys.any_pressed(MODIFIER_KEYS)
+    }
+
+    fn binding_pressed(&self, binding: &Binding) -> bool {
+        match *binding {
+            Binding::Key(key) => self.keys.pressed(key),
+            Binding::CtrlKey(key) => self.control() && self.keys.pressed(key),
+            Binding::Mouse(button) => self.mouse.pressed(button),
+            Binding::Gamepad(button) => self.gamepads.iter().any(|pad| pad.pressed(button)),
+            Binding::Axis { axis, positive } => self
+                .gamepads
+                .iter()
+                .any(|pad| axis_pushed(pad, axis, positive)),
+        }
+    }
+
+    fn binding_just_pressed(&self, binding: &Binding) -> bool {
+        match *binding {
+            Binding::Key(key) => self.keys.just_pressed(key),
+            Binding::CtrlKey(key) => self.control() && self.keys.just_pressed(key),
+            Binding::Mouse(button) => self.mouse.just_pressed(button),
+            Binding::Gamepad(button) => self.gamepads.iter().any(|pad| pad.just_pressed(button)),
+            Binding::Axis { .. } => false,
+        }
+    }
+}
+
+fn axis_pushed(gamepad: &Gamepad, axis: GamepadAxis, positive: bool) -> bool {
+    gamepad.get(axis).is_some_and(|value| {
+        if positive {
+            value > AXIS_THRESHOLD
+        } else {
+            value < -AXIS_THRESHOLD
+        }
+    })
+}
+
+/// The state of the rebinding screen
+#[derive(Resource, Debug, Default)]
+pub struct BindingScreen {
+    pub open: bool,
+    /// index into `Action::ALL`
+    selected: usize,
+    /// waiting for the input to bind to the selected action
+    capturing: bool,
+}
+
+/// Whether neither the rebinding screen nor the console is taking the input, for input read
+/// outside the actions, such as the debug keys, the toolbar and the scroll wheel
+pub fn input_unclaimed(screen: Res<BindingScreen>, console: Res<ConsoleOpen>) -> bool {
+    !screen.open && !console.open
+}
+
+#[derive(Component)]
+struct BindingScreenText;
+
+fn setup(column: Single<Entity, With<RightColumn>>, mut commands: Commands) {
+    // Text to list the actions and their bindings, below the statistics
+    commands
+        .entity(*column)
+        .with_child((Text::new(""), BindingScreenText));
+}
+
+fn toggle_binding_screen(keys: Res<ButtonInput<KeyCode>>, mut screen: ResMut<BindingScreen>) {
+    if keys.just_pressed(BINDINGS_KEY) {
+        screen.open = !screen.open;
+        screen.capturing = false;
+    }
+}
+
+/// Picks an action with the arrow keys or d-pad, and rebinds, clears or restores it
+fn binding_screen_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    mut screen: ResMut<BindingScreen>,
+    mut bindings: ResMut<Bindings>,
+) {
+    if !screen.open {
+        return;
+    }
+    let action = Action::ALL[screen.selected];
+
+    //  while waiting, the press is the new binding rather than a command
+    if screen.capturing {
+        if keys.just_pressed(CANCEL_KEY) {
+            screen.capturing = false;
+        } else if let Some(binding) = captured(&keys, &mouse, &gamepads) {
+            bindings.rebind(action, binding);
+            bindings.save();
+            screen.capturing = false;
+        }
+        return;
+    }
+
+    let pressed = |key: KeyCode, button: GamepadButton| {
+        keys.just_pressed(key) || gamepads.iter().any(|pad| pad.just_pressed(button))
+    };
+    let count = Action::ALL.len();
+    if pressed(KeyCode::ArrowUp, GamepadButton::DPadUp) {
+        screen.selected = (screen.selected + count - 1) % count;
+    } else if pressed(KeyCode::ArrowDown, GamepadButton::DPadDown) {
+        screen.selected = (screen.selected + 1) % count;
+    } else if pressed(KeyCode::Enter, GamepadButton::South) {
+        screen.capturing = true;
+    } else if pressed(CLEAR_KEY, GamepadButton::West) {
+        bindings.0.insert(action, Vec::new());
+        bindings.save();
+    } else if pressed(DEFAULT_KEY, GamepadButton::North) {
+        bindings.0.insert(action, action.default_bindings());
+        bindings.save();
+    } else if pressed(CANCEL_KEY, GamepadButton::Start) {
+        screen.open = false;
+    }
+}
+
+/// The first key, mouse button, gamepad button or stick pressed this frame
+fn captured(
+    keys: &ButtonInput<KeyCode>,
+    mouse: &ButtonInput<MouseButton>,
+    gamepads: &Query<&Gamepad>,
+) -> Option<Binding> {
+    //  Ctrl on its own is not a binding, only a part of one
+    let control = keys.any_pressed(MODIFIER_KEYS);
+    let key = keys
+        .get_just_pressed()
+        .find(|key| !MODIFIER_KEYS.contains(key))
+        .map(|key| {
+            if control {
+                Binding::CtrlKey(*key)
+            } else {
+                Binding::Key(*key)
+            }
+        });
+    let button = || mouse.get_just_pressed().next().map(|b| Binding::Mouse(*b));
+    let pad = || {
+        gamepads.iter().find_map(|gamepad| {
+            gamepad
+                .get_just_pressed()
+                .next()
+                .map(|b| Binding::Gamepad(*b))
+                .or_else(|| {
+                    STICKS.into_iter().find_map(|axis| {
+                        [true, false]
+                            .into_iter()
+                            .find(|positive| axis_pushed(gamepad, axis, *positive))
+                            .map(|positive| Binding::Axis { axis, positive })
+                    })
+                })
+        })
+    };
+
+    key.or_else(button).or_else(pad)
+}
+
+fn update_binding_display(
+    screen: Res<BindingScreen>,
+    bindings: Res<Bindings>,
+    mut query: Query<&mut Text, With<BindingScreenText>>,
+) {
+    if !(screen.is_changed() || bindings.is_changed()) {
+        return;
+    }
+
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+
+    if !screen.open {
+        **text = String::new();
+        return;
+    }
+
+    let mut lines = vec![String::from(
+        "Controls: Enter or A rebinds, Delete or X clears, Home or Y restores, Esc closes",
+    )];
+    for (index, action) in Action::ALL.iter().enumerate() {
+        let marker = if index == screen.selected { ">" } else { " " };
+        let bound = if index == screen.selected && screen.capturing {
+            String::from("press a key or button, Esc to cancel")
+        } else {
+            bindings.describe(*action)
+        };
+        lines.push(format!("{} {}: {}", marker, action.name(), bound));
+    }
+
+    **text = lines.join("\n");
+}
@@ -0,0 +1,314 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bindings::{Action, ActionInput},
+    editor::GameMode,
+    goals::LevelGoals,
+    map::LoadMap,
+    scoring::LevelScored,
+};
+
+const CAMPAIGN_PATH: &str = "./assets/campaign.json";
+const PROGRESS_DIRECTORY: &str = "./saves";
+const PROGRESS_PATH: &str = "./saves/progress.json";
+
+/// The levels the slots pick, the tenth slot goes unused
+const LEVEL_SLOTS: usize = 9;
+
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Campaign::load())
+            .insert_resource(CampaignProgress::load())
+            .insert_resource(CurrentLevel::default())
+            .insert_resource(LevelSelectOpen(false));
+
+        app.add_event::<SelectLevel>();
+
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (
+                (
+                    toggle_level_select.run_if(in_state(GameMode::Play)),
+                    level_select_keys,
+                    select_level,
+                )
+                    .chain(),
+                (forget_current_level, record_completion),
+                update_level_select_display,
+            ),
+        );
+    }
+}
+
+/// The ordered list of levels, read from the campaign manifest
+#[derive(Resource, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Campaign {
+    pub levels: Vec<CampaignLevel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignLevel {
+    /// the map file name, without extension
+    pub map: String,
+    pub title: String,
+    /// maps that must be completed before this level unlocks
+    #[serde(default)]
+    pub requires: Vec<String>,
+}
+
+impl Campaign {
+    fn load() -> Self {
+        let json_data = match fs::read_to_string(CAMPAIGN_PATH) {
+            Err(e) => {
+                warn!("failed to read campaign manifest: {:?}", e);
+                return Self::default();
+            }
+            Ok(data) => data,
+        };
+
+        match serde_json::from_str(&json_data) {
+            Err(e) => {
+                warn!("failed to deserialize campaign manifest: {:?}", e);
+                Self::default()
+            }
+            Ok(campaign) => campaign,
+        }
+    }
+}
+
+/// Completed levels and best results, persisted to the save file
+#[derive(Resource, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CampaignProgress {
+    pub levels: HashMap<String, LevelRecord>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct LevelRecord {
+    pub completed: bool,
+    pub best_time: Option<f32>,
+    #[serde(default)]
+    pub best_score: Option<u32>,
+    #[serde(default)]
+    pub best_stars: Option<u32>,
+}
+
+impl CampaignProgress {
+    fn load() -> Self {
+        let Ok(json_data) = fs::read_to_string(PROGRESS_PATH) else {
+            //  no save file yet
+            return Self::default();
+        };
+
+        match serde_json::from_str(&json_data) {
+            Err(e) => {
+                warn!("failed to deserialize campaign progress: {:?}", e);
+                Self::default()
+            }
+            Ok(progress) => progress,
+        }
+    }
+
+    fn save(&self) {
+        let json_data = match serde_json::to_string_pretty(self) {
+            Err(e) => {
+                warn!("failed to serialize campaign progress: {:?}", e);
+                return;
+            }
+            Ok(data) => data,
+        };
+
+        if let Err(e) = fs::create_dir_all(Path::new(PROGRESS_DIRECTORY))
+            .and_then(|_| fs::write(PROGRESS_PATH, json_data))
+        {
+            warn!("failed to write campaign progress: {:?}", e);
+        }
+    }
+
+    pub fn is_completed(&self, map: &str) -> bool {
+        self.levels.get(map).is_some_and(|record| record.completed)
+    }
+
+    pub fn is_unlocked(&self, level: &CampaignLevel) -> bool {
+        level.requires.iter().all(|map| self.is_completed(map))
+    }
+}
+
+/// The campaign level being played, if any
+#[derive(Resource, Debug, Default)]
+pub struct CurrentLevel(pub Option<usize>);
+
+#[derive(Resource, Debug)]
+pub struct LevelSelectOpen(pub bool);
+
+/// Loads a campaign level by its index, if it is unlocked
+#[derive(Event, Debug)]
+pub struct SelectLevel {
+    pub index: usize,
+}
+
+#[derive(Component)]
+struct LevelSelectText;
+
+fn setup(mut commands: Commands) {
+    // Text to list the campaign levels
+    commands.spawn((
+        Text::new(""),
+        LevelSelectText,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(84.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+fn toggle_level_select(input: ActionInput, mut open: ResMut<LevelSelectOpen>) {
+    if input.just_pressed(Action::LevelSelect) {
+        open.0 = !open.0;
+    }
+}
+
+/// Picks a level by its number, the tool keys leave the slots alone while this is open
+fn level_select_keys(
+    input: ActionInput,
+    mut open: ResMut<LevelSelectOpen>,
+    mut select: EventWriter<SelectLevel>,
+) {
+    if !open.0 {
+        return;
+    }
+
+    for (index, action) in Action::SLOTS[..LEVEL_SLOTS].iter().enumerate() {
+        if input.just_pressed(*action) {
+            select.send(SelectLevel { index });
+            open.0 = false;
+        }
+    }
+}
+
+fn select_level(
+    mut event: EventReader<SelectLevel>,
+    campaign: Res<Campaign>,
+    progress: Res<CampaignProgress>,
+    mut current: ResMut<CurrentLevel>,
+    mut load: EventWriter<LoadMap>,
+) {
+    for selection in event.read() {
+        let Some(level) = campaign.levels.get(selection.index) else {
+            warn!("no campaign level at index {}", selection.index);
+            continue;
+        };
+
+        if !progress.is_unlocked(level) {
+            info!("level {:?} is locked", level.title);
+            continue;
+        }
+
+        current.0 = Some(selection.index);
+        load.send(LoadMap {
+            name: level.map.clone(),
+        });
+    }
+}
+
+/// A map loaded outside of the level select is no longer the campaign level
+fn forget_current_level(
+    mut event: EventReader<LoadMap>,
+    campaign: Res<Campaign>,
+    mut current: ResMut<CurrentLevel>,
+) {
+    for load in event.read() {
+        let Some(level) = current.0.and_then(|index| campaign.levels.get(index)) else {
+            continue;
+        };
+
+        if level.map != load.name {
+            current.0 = None;
+        }
+    }
+}
+
+fn record_completion(
+    mut event: EventReader<LevelScored>,
+    goals: Res<LevelGoals>,
+    campaign: Res<Campaign>,
+    current: Res<CurrentLevel>,
+    mut progress: ResMut<CampaignProgress>,
+) {
+    for result in event.read() {
+        if !result.completed {
+            continue;
+        }
+
+        let Some(level) = current.0.and_then(|index| campaign.levels.get(index)) else {
+            continue;
+        };
+
+        let record = progress.levels.entry(level.map.clone()).or_default();
+        record.completed = true;
+        record.best_time = Some(match record.best_time {
+            Some(best) => best.min(goals.elapsed),
+            None => goals.elapsed,
+        });
+        record.best_score = Some(record.best_score.unwrap_or(0).max(result.score));
+        record.best_stars = Some(record.best_stars.unwrap_or(0).max(result.stars));
+
+        progress.save();
+    }
+}
+
+fn update_level_select_display(
+    open: Res<LevelSelectOpen>,
+    campaign: Res<Campaign>,
+    progress: Res<CampaignProgress>,
+    current: Res<CurrentLevel>,
+    mut query: Query<&mut Text, With<LevelSelectText>>,
+) {
+    if !(open.is_changed() || progress.is_changed() || current.is_changed()) {
+        return;
+    }
+
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+
+    if !open.0 {
+        **text = String::new();
+        return;
+    }
+
+    let mut lines = vec![String::from("Select a level:")];
+    for (index, level) in campaign.levels.iter().enumerate() {
+        let status = match progress.levels.get(&level.map) {
+            _ if !progress.is_unlocked(level) => String::from("locked"),
+            Some(LevelRecord {
+                completed: true,
+                best_time: Some(time),
+                best_score,
+                best_stars,
+            }) => format!(
+                "best {:.1}s, {} points, {} stars",
+                time,
+                best_score.unwrap_or(0),
+                best_stars.unwrap_or(0)
+            ),
+            _ => String::from("open"),
+        };
+        let marker = if current.0 == Some(index) { ">" } else { " " };
+        lines.push(format!(
+            "{} {}. {} ({})",
+            marker,
+            index + 1,
+            level.title,
+            status
+        ));
+    }
+
+    **text = lines.join("\n");
+}
@@ -0,0 +1,196 @@
+use bevy::{input::mouse::MouseButtonInput, prelude::*};
+
+use crate::{
+    bindings::{Action, ActionInput},
+    editor::GameMode,
+    flying_camera::CameraRoot,
+    grid::GridCell,
+    ground::Ground,
+    map::GenerateMap,
+    selection::{GroundSelected, HoverMaterial, HoveredGround},
+    soil::{Soil, SoilMaterials},
+    tools::ToolPalette,
+};
+
+/// Steps on screen, up being away from the camera
+const MOVES: [(Action, IVec2); 4] = [
+    (Action::CursorUp, IVec2::Y),
+    (Action::CursorDown, IVec2::NEG_Y),
+    (Action::CursorLeft, IVec2::NEG_X),
+    (Action::CursorRight, IVec2::X),
+];
+
+const PANS: [Action; 4] = [
+    Action::PanForward,
+    Action::PanBack,
+    Action::PanLeft,
+    Action::PanRight,
+];
+
+pub struct CursorPlugin;
+
+impl Plugin for CursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GridCursor::default());
+
+        app.add_systems(
+            Update,
+            (
+                (
+                    (hide_cursor, move_cursor, press_cursor).chain(),
+                    cycle_tools,
+                )
+                    .run_if(in_state(GameMode::Play)),
+                clear_cursor,
+                highlight_cursor.run_if(resource_changed::<GridCursor>),
+            ),
+        );
+    }
+}
+
+/// The ground cell picked with the arrow keys or d-pad, for playing without a mouse
+#[derive(Resource, Debug, Default)]
+pub struct GridCursor(pub Option<Entity>);
+
+/// The mouse takes over again once it moves or clicks, its click already picks a cell
+fn hide_cursor(
+    mut moved: EventReader<CursorMoved>,
+    mut clicked: EventReader<MouseButtonInput>,
+    mut cursor: ResMut<GridCursor>,
+) {
+    //  both are read through, so old events never hide the cursor later
+    let moved = moved.read().last().is_some();
+    let clicked = clicked.read().last().is_some();
+    if (moved || clicked) && cursor.0.is_some() {
+        cursor.0 = None;
+    }
+}
+
+/// Steps the cursor to the next cell in the direction pressed, as seen from the camera,
+/// or puts it on the map or takes it off
+fn move_cursor(
+    input: ActionInput,
+    root: Single<&GlobalTransform, With<CameraRoot>>,
+    grounds: Query<(Entity, &GridCell), With<Ground>>,
+    mut cursor: ResMut<GridCursor>,
+    mut hovered: ResMut<HoveredGround>,
+) {
+    let toggled = input.just_pressed(Action::ToggleCursor);
+    if toggled && cursor.0.is_some() {
+        cursor.0 = None;
+        return;
+    }
+
+    //  while the cursor is off the map, the keys it shares with panning only pan, the d-pad
+    //  puts it on the map
+    let panning = PANS.iter().any(|action| input.pressed(*action));
+    let step = MOVES
+        .iter()
+        .find(|(action, _)| input.just_pressed(*action))
+        .map(|(_, step)| *step)
+        .filter(|_| cursor.0.is_some() || !panning);
+    if !toggled && step.is_none() {
+        return;
+    }
+
+    //  start from the cell under the pointer, or the middle of the map
+    let start = cursor
+        .0
+        .or(hovered.0)
+        .and_then(|ground| grounds.get(ground).ok())
+        .map(|(_, cell)| cell.coordinates());
+    let target = match (start, step) {
+        (Some(start), Some(step)) => start + grid_step(root.rotation(), step),
+        (Some(start), None) => start,
+        (None, _) => {
+            let size = grounds
+                .iter()
+                .map(|(_, cell)| cell.coordinates())
+                .reduce(IVec2::max)
+                .unwrap_or_default();
+            size / 2
+        }
+    };
+
+    //  the cursor stays put at the edge of the map
+    if let Some((ground, _)) = grounds
+        .iter()
+        .find(|(_, cell)| cell.coordinates() == target)
+    {
+        cursor.0 = Some(ground);
+        hovered.0 = Some(ground);
+    }
+}
+
+/// The grid step closest to a step on screen, with the camera turned by `rotation`
+fn grid_step(rotation: Quat, step: IVec2) -> IVec2 {
+    //  rows run along x and columns along z, and up on screen is away from the camera
+    let direction = rotation * Vec3::new(step.x as f32, 0.0, -step.y as f32);
+    if direction.x.abs() >= direction.z.abs() {
+        IVec2::new(direction.x.signum() as i32, 0)
+    } else {
+        IVec2::new(0, direction.z.signum() as i32)
+    }
+}
+
+/// Uses the tool on the cursor's cell, as a left or right click would
+fn press_cursor(
+    input: ActionInput,
+    cursor: Res<GridCursor>,
+    mut ground_selected: EventWriter<GroundSelected>,
+) {
+    let Some(ground) = cursor.0 else {
+        return;
+    };
+
+    let inverse = if input.just_pressed(Action::UseTool) {
+        false
+    } else if input.just_pressed(Action::UseOpposite) {
+        true
+    } else {
+        return;
+    };
+    ground_selected.send(GroundSelected {
+        entity: ground,
+        inverse,
+    });
+}
+
+/// Steps through the toolbar, for gamepads where number keys are out of reach
+fn cycle_tools(input: ActionInput, mut palette: ResMut<ToolPalette>) {
+    if input.just_pressed(Action::PreviousTool) {
+        palette.cycle(false);
+    }
+    if input.just_pressed(Action::NextTool) {
+        palette.cycle(true);
+    }
+}
+
+/// The cell of an old map is gone with it
+fn clear_cursor(mut event: EventReader<GenerateMap>, mut cursor: ResMut<GridCursor>) {
+    if event.read().last().is_some() {
+        cursor.0 = None;
+    }
+}
+
+/// Lights up the cursor's cell as the pointer lights up a hovered one
+fn highlight_cursor(
+    cursor: Res<GridCursor>,
+    hover_material: Option<Res<HoverMaterial>>,
+    soil_materials: Option<Res<SoilMaterials>>,
+    mut grounds: Query<(&Soil, &mut MeshMaterial3d<StandardMaterial>), With<Ground>>,
+    mut highlighted: Local<Option<Entity>>,
+) {
+    let (Some(hover_material), Some(soil_materials)) = (hover_material, soil_materials) else {
+        return;
+    };
+
+    if let Some(Ok((soil, mut material))) = highlighted.take().map(|ground| grounds.get_mut(ground))
+    {
+        material.0 = soil_materials.0[soil].clone();
+    }
+    if let Some(Ok((_, mut material))) = cursor.0.map(|ground| grounds.get_mut(ground)) {
+        material.0 = hover_material.0.clone();
+        *highlighted = cursor.0;
+    }
+}
@@ -0,0 +1,105 @@
+use bevy::prelude::*;
+use bevy_console::{AddConsoleCommand, ConsoleCommand};
+use clap::Parser;
+
+use crate::bindings::{Action, Binding, Bindings};
+
+pub struct BindingsCommandsPlugin;
+
+impl Plugin for BindingsCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_console_command::<BindCommand, _>(bind_command)
+            .add_console_command::<BindingsCommand, _>(bindings_command);
+    }
+}
+
+/// bind inputs to an action, such as key:KeyW, ctrl:KeyZ, mouse:Middle, pad:South or axis:LeftStickX+
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "bind")]
+struct BindCommand {
+    /// the action, such as pan-forward or use-tool
+    action: String,
+    /// the inputs to bind, printing the current ones if there are none
+    inputs: Vec<String>,
+    /// keep the inputs already bound
+    #[arg(long)]
+    add: bool,
+    /// go back to the default inputs
+    #[arg(long, conflicts_with_all = ["inputs", "add"])]
+    reset: bool,
+    /// leave the action without any inputs
+    #[arg(long, conflicts_with_all = ["inputs", "add", "reset"])]
+    clear: bool,
+}
+
+fn bind_command(mut log: ConsoleCommand<BindCommand>, mut bindings: ResMut<Bindings>) {
+    if let Some(Ok(BindCommand {
+        action,
+        inputs,
+        add,
+        reset,
+        clear,
+    })) = log.take()
+    {
+        let Some(action) = Action::from_name(&action) else {
+            log.reply(format!("error (bind): unknown action {:?}.", action));
+            return;
+        };
+
+        let mut parsed = Vec::new();
+        for input in inputs.iter() {
+            let Some(binding) = Binding::from_name(input) else {
+                log.reply(format!("error (bind): unknown input {:?}.", input));
+                return;
+            };
+            parsed.push(binding);
+        }
+
+        if reset {
+            bindings.0.insert(action, action.default_bindings());
+        } else if clear {
+            bindings.0.insert(action, Vec::new());
+        } else if !parsed.is_empty() {
+            let bound = bindings.0.entry(action).or_default();
+            if !add {
+                bound.clear();
+            }
+            bound.extend(parsed);
+        }
+        if reset || clear || !inputs.is_empty() {
+            bindings.save();
+        }
+
+        log.reply(format!(
+            "\t{}: {}.",
+            action.name(),
+            bindings.describe(action)
+        ));
+    }
+}
+
+/// list every action and its inputs
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "bindings")]
+struct BindingsCommand {
+    /// go back to the default inputs for every action
+    #[arg(long)]
+    reset: bool,
+}
+
+fn bindings_command(mut log: ConsoleCommand<BindingsCommand>, mut bindings: ResMut<Bindings>) {
+    if let Some(Ok(BindingsCommand { reset })) = log.take() {
+        if reset {
+            *bindings = Bindings::default();
+            bindings.save();
+        }
+
+        for action in Action::ALL {
+            log.reply(format!(
+                "\t{}: {}",
+                action.name(),
+                bindings.describe(action)
+            ));
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+use bevy_console::{AddConsoleCommand, ConsoleCommand};
+use clap::Parser;
+
+use crate::tools::{
+    brush::{Brush, BrushShape, BRUSH_RADIUS_MAX, BRUSH_REPEAT_MAX, BRUSH_STRENGTH_MAX},
+    undo,
+};
+
+pub struct BrushCommandsPlugin;
+
+impl Plugin for BrushCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_console_command::<BrushCommand, _>(brush_command)
+            .add_console_command::<UndoCommand, _>(undo_command);
+    }
+}
+
+/// change the brush the tools use, or print it
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "brush")]
+struct BrushCommand {
+    /// square or circle
+    #[arg(long)]
+    shape: Option<String>,
+    /// cells from the center to the edge
+    #[arg(long, value_parser = clap::value_parser!(i32).range(0..=BRUSH_RADIUS_MAX as i64))]
+    radius: Option<i32>,
+    /// layers changed by each use
+    #[arg(long, value_parser = clap::value_parser!(i32).range(1..=BRUSH_STRENGTH_MAX as i64))]
+    strength: Option<i32>,
+    /// layer the flatten tool levels to
+    #[arg(long, allow_hyphen_values = true)]
+    target: Option<i32>,
+    /// flatten to the clicked cell's layer again
+    #[arg(long, conflicts_with = "target")]
+    clear_target: bool,
+    /// uses per second while a button is held on one cell, 0 for once
+    #[arg(long, value_parser = parse_repeat)]
+    repeat: Option<f32>,
+}
+
+fn brush_command(mut log: ConsoleCommand<BrushCommand>, mut brush: ResMut<Brush>) {
+    if let Some(Ok(BrushCommand {
+        shape,
+        radius,
+        strength,
+        target,
+        clear_target,
+        repeat,
+    })) = log.take()
+    {
+        if let Some(shape) = shape {
+            let Some(shape) = BrushShape::from_name(&shape) else {
+                log.reply(format!("error (brush): unknown shape {:?}.", shape));
+                return;
+            };
+            brush.shape = shape;
+        }
+        if let Some(radius) = radius {
+            brush.radius = radius;
+        }
+        if let Some(strength) = strength {
+            brush.strength = strength;
+        }
+        if target.is_some() || clear_target {
+            brush.target = target;
+        }
+        if let Some(repeat) = repeat {
+            brush.repeat = repeat;
+        }
+
+        log.reply(format!("\t{}.", brush.describe()));
+    }
+}
+
+/// parses uses per second, from 0 up to the most the brush repeats
+fn parse_repeat(arg: &str) -> Result<f32, String> {
+    let repeat = arg
+        .trim()
+        .parse::<f32>()
+        .map_err(|e| format!("{:?} is not a number: {}", arg, e))?;
+    if !(0.0..=BRUSH_REPEAT_MAX).contains(&repeat) {
+        return Err(format!("{} is not in 0..={}", repeat, BRUSH_REPEAT_MAX));
+    }
+    Ok(repeat)
+}
+
+/// undo the last edit, as Ctrl+Z does
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "undo")]
+struct UndoCommand;
+
+fn undo_command(mut log: ConsoleCommand<UndoCommand>, mut commands: Commands) {
+    if let Some(Ok(UndoCommand)) = log.take() {
+        commands.queue(|world: &mut World| {
+            if !undo::undo(world) {
+                warn!("nothing to undo");
+            }
+        });
+        log.reply("\tundoing the last edit.");
+    }
+}
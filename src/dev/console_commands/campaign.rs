@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use bevy_console::{AddConsoleCommand, ConsoleCommand};
+use clap::Parser;
+
+use crate::campaign::{Campaign, CampaignProgress, SelectLevel};
+
+pub struct CampaignCommandsPlugin;
+
+impl Plugin for CampaignCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_console_command::<LevelsCommand, _>(levels_command)
+            .add_console_command::<PlayLevelCommand, _>(play_level_command);
+    }
+}
+
+/// list the campaign levels
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "levels")]
+struct LevelsCommand;
+
+fn levels_command(
+    mut log: ConsoleCommand<LevelsCommand>,
+    campaign: Res<Campaign>,
+    progress: Res<CampaignProgress>,
+) {
+    if let Some(Ok(LevelsCommand)) = log.take() {
+        for (index, level) in campaign.levels.iter().enumerate() {
+            let status = if progress.is_completed(&level.map) {
+                "completed"
+            } else if progress.is_unlocked(level) {
+                "open"
+            } else {
+                "locked"
+            };
+            log.reply(format!("\t{}. {} [{}] - {}", index + 1, level.title, level.map, status));
+        }
+    }
+}
+
+/// play a campaign level
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "play-level")]
+struct PlayLevelCommand {
+    /// level number, as listed by `levels`
+    number: usize,
+}
+
+fn play_level_command(
+    mut log: ConsoleCommand<PlayLevelCommand>,
+    mut select: EventWriter<SelectLevel>,
+) {
+    if let Some(Ok(PlayLevelCommand { number })) = log.take() {
+        if number == 0 {
+            log.reply("error (level number): levels are numbered from 1.");
+            return;
+        }
+        select.send(SelectLevel { index: number - 1 });
+    }
+}
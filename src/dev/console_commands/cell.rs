@@ -0,0 +1,487 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_console::{AddConsoleCommand, ConsoleCommand};
+use clap::{Parser, Subcommand};
+
+use crate::{
+    editor::GameMode,
+    fluid_dynamics::Draining,
+    grid::GridCell,
+    ground::Ground,
+    neighborhood::Neighborhood,
+    pair::Pair,
+    scoring::EditBudget,
+    selection::SelectedCells,
+    shifting::{Locked, ShiftCell},
+    soil::Soil,
+    tools::{
+        begin_edit,
+        select::{self, SelectMode},
+    },
+    tuning::Tuning,
+    water::{ManuallyIncreaseWater, Water, WaterSink, WaterSource},
+};
+
+pub struct CellCommandsPlugin;
+
+impl Plugin for CellCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_console_command::<CellCommand, _>(cell_command)
+            .add_console_command::<SetLayerCommand, _>(set_layer_command)
+            .add_console_command::<AddWaterCommand, _>(add_water_command)
+            .add_console_command::<LockCommand, _>(lock_command)
+            .add_console_command::<SelectCommand, _>(select_command);
+    }
+}
+
+/// the selected cells, in place of a row and column
+#[derive(Subcommand)]
+enum OnSelection {
+    /// the selected cells
+    Selection,
+}
+
+/// print a cell's layer, soil, water and neighbors
+#[derive(Parser, ConsoleCommand)]
+#[command(
+    name = "cell",
+    allow_negative_numbers = true,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct CellCommand {
+    #[command(subcommand)]
+    selection: Option<OnSelection>,
+    #[arg(required = true)]
+    row: Option<i32>,
+    #[arg(required = true)]
+    col: Option<i32>,
+    /// opposite corner of a region, as row,col
+    #[arg(long, value_parser = parse_cell)]
+    to: Option<IVec2>,
+}
+
+fn cell_command(
+    mut log: ConsoleCommand<CellCommand>,
+    tuning: Res<Tuning>,
+    selected: Res<SelectedCells>,
+    grounds: Query<(Entity, &GridCell, &Neighborhood, &Soil), With<Ground>>,
+    pairs: Query<&Pair>,
+    waters: Query<(&Water, Option<&Draining>)>,
+    flows: Query<(Option<&WaterSource>, Option<&WaterSink>)>,
+) {
+    if let Some(Ok(CellCommand { selection, row, col, to })) = log.take() {
+        let cells: HashMap<IVec2, Entity> = grounds
+            .iter()
+            .map(|(entity, cell, _, _)| (cell.coordinates(), entity))
+            .collect();
+
+        let Some(targets) = targets(selection.is_some(), row, col, to, &selected, &cells) else {
+            log.reply("error (selection): no cells are selected.");
+            return;
+        };
+        for coordinates in targets {
+            let Some((entity, cell, neighborhood, soil)) = cells
+                .get(&coordinates)
+                .and_then(|entity| grounds.get(*entity).ok())
+            else {
+                log.reply(format!("error (cell): no cell at {}, {}.", coordinates.x, coordinates.y));
+                continue;
+            };
+
+            log.reply(format!(
+                "\tcell {}, {}: layer {}, {:?}",
+                cell.row,
+                cell.col,
+                cell.layer_index(tuning.cell_height),
+                soil
+            ));
+
+            let water_entity = pairs
+                .iter()
+                .find(|pair| pair.ground == entity)
+                .map(|pair| pair.water)
+                .unwrap_or(Entity::PLACEHOLDER);
+            if let Ok((water, draining)) = waters.get(water_entity) {
+                let mut line = format!("\t\twater {:.2}", water.amount);
+                match draining {
+                    Some(draining) => line += &format!(", draining at {}", draining.rate),
+                    None => line += ", not draining",
+                }
+                let (source, sink) = flows.get(water_entity).unwrap_or_default();
+                if let Some(source) = source {
+                    line += &format!(", source {}/s", source.rate);
+                }
+                if let Some(sink) = sink {
+                    line += &format!(", sink {}/s", sink.rate);
+                }
+                log.reply(line);
+            }
+
+            let neighbors: Vec<String> = neighborhood
+                .get_neighbors()
+                .iter()
+                .filter_map(|entity| grounds.get(*entity).ok())
+                .map(|(_, neighbor, _, _)| {
+                    let layer = neighbor.layer_index(tuning.cell_height);
+                    format!("{}, {} (layer {})", neighbor.row, neighbor.col, layer)
+                })
+                .collect();
+            log.reply(format!("\t\tneighbors: {}", neighbors.join("; ")));
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum SetLayerOnSelection {
+    /// shift the selected cells
+    #[command(allow_negative_numbers = true)]
+    Selection { layer: i32 },
+}
+
+/// shift a cell to a layer, like clicking it
+#[derive(Parser, ConsoleCommand)]
+#[command(
+    name = "set-layer",
+    allow_negative_numbers = true,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct SetLayerCommand {
+    #[command(subcommand)]
+    selection: Option<SetLayerOnSelection>,
+    #[arg(required = true)]
+    row: Option<i32>,
+    #[arg(required = true)]
+    col: Option<i32>,
+    #[arg(required = true)]
+    layer: Option<i32>,
+    /// pull neighbors along, as a click would
+    #[arg(long, global = true)]
+    cascade: bool,
+    /// opposite corner of a region, as row,col
+    #[arg(long, value_parser = parse_cell)]
+    to: Option<IVec2>,
+}
+
+fn set_layer_command(
+    mut log: ConsoleCommand<SetLayerCommand>,
+    mode: Res<State<GameMode>>,
+    tuning: Res<Tuning>,
+    selected: Res<SelectedCells>,
+    budget: Res<EditBudget>,
+    grounds: Query<(Entity, &GridCell), With<Ground>>,
+    mut commands: Commands,
+) {
+    if let Some(Ok(SetLayerCommand { selection, row, col, layer, cascade, to })) = log.take() {
+        if *mode.get() != GameMode::Play {
+            log.reply("error (mode): cells can only be changed while playing.");
+            return;
+        }
+
+        let cells: HashMap<IVec2, Entity> = grounds
+            .iter()
+            .map(|(entity, cell)| (cell.coordinates(), entity))
+            .collect();
+
+        let layer = match selection {
+            Some(SetLayerOnSelection::Selection { layer }) => layer,
+            None => layer.unwrap_or_default(),
+        };
+        let Some(targets) = targets(selection.is_some(), row, col, to, &selected, &cells) else {
+            log.reply("error (selection): no cells are selected.");
+            return;
+        };
+        if !budget.can_edit() {
+            log.reply("error (cell): no edits left for this level.");
+            return;
+        }
+
+        let mut shifts = Vec::new();
+        for coordinates in targets {
+            let Some((entity, cell)) = cells
+                .get(&coordinates)
+                .and_then(|entity| grounds.get(*entity).ok())
+            else {
+                log.reply(format!("error (cell): no cell at {}, {}.", coordinates.x, coordinates.y));
+                continue;
+            };
+
+            shifts.push(ShiftCell {
+                entity,
+                layers: layer - cell.layer_index(tuning.cell_height),
+                cascade,
+            });
+        }
+        queue_edit(&mut commands, shifts);
+    }
+}
+
+#[derive(Subcommand)]
+enum AddWaterOnSelection {
+    /// add water to the selected cells
+    #[command(allow_negative_numbers = true)]
+    Selection { amount: f32 },
+}
+
+/// add water to a cell, like filling it with the water tool
+#[derive(Parser, ConsoleCommand)]
+#[command(
+    name = "add-water",
+    allow_negative_numbers = true,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct AddWaterCommand {
+    #[command(subcommand)]
+    selection: Option<AddWaterOnSelection>,
+    #[arg(required = true)]
+    row: Option<i32>,
+    #[arg(required = true)]
+    col: Option<i32>,
+    /// water height to add, negative to remove
+    #[arg(required = true)]
+    amount: Option<f32>,
+    /// opposite corner of a region, as row,col
+    #[arg(long, value_parser = parse_cell)]
+    to: Option<IVec2>,
+}
+
+fn add_water_command(
+    mut log: ConsoleCommand<AddWaterCommand>,
+    mode: Res<State<GameMode>>,
+    selected: Res<SelectedCells>,
+    budget: Res<EditBudget>,
+    grounds: Query<(Entity, &GridCell), With<Ground>>,
+    mut commands: Commands,
+) {
+    if let Some(Ok(AddWaterCommand { selection, row, col, amount, to })) = log.take() {
+        if *mode.get() != GameMode::Play {
+            log.reply("error (mode): water can only be added while playing.");
+            return;
+        }
+
+        let cells: HashMap<IVec2, Entity> = grounds
+            .iter()
+            .map(|(entity, cell)| (cell.coordinates(), entity))
+            .collect();
+
+        let amount = match selection {
+            Some(AddWaterOnSelection::Selection { amount }) => amount,
+            None => amount.unwrap_or_default(),
+        };
+        let Some(targets) = targets(selection.is_some(), row, col, to, &selected, &cells) else {
+            log.reply("error (selection): no cells are selected.");
+            return;
+        };
+        if !budget.can_edit() {
+            log.reply("error (cell): no edits left for this level.");
+            return;
+        }
+
+        let mut pours = Vec::new();
+        for coordinates in targets {
+            let Some(entity) = cells.get(&coordinates) else {
+                log.reply(format!("error (cell): no cell at {}, {}.", coordinates.x, coordinates.y));
+                continue;
+            };
+
+            pours.push(ManuallyIncreaseWater {
+                ground: *entity,
+                amount,
+            });
+        }
+        queue_edit(&mut commands, pours);
+    }
+}
+
+/// Sends a command's changes as one edit of the budget and one step to undo, as a click would
+fn queue_edit<E: Event>(commands: &mut Commands, events: Vec<E>) {
+    if events.is_empty() {
+        return;
+    }
+
+    commands.queue(move |world: &mut World| {
+        if begin_edit(world, true) {
+            for event in events {
+                world.send_event(event);
+            }
+        }
+    });
+}
+
+/// hold cells in place, so neither clicks nor cascades shift them
+#[derive(Parser, ConsoleCommand)]
+#[command(
+    name = "lock",
+    allow_negative_numbers = true,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct LockCommand {
+    #[command(subcommand)]
+    selection: Option<OnSelection>,
+    #[arg(required = true)]
+    row: Option<i32>,
+    #[arg(required = true)]
+    col: Option<i32>,
+    /// opposite corner of a region, as row,col
+    #[arg(long, value_parser = parse_cell)]
+    to: Option<IVec2>,
+    /// let the cells shift again
+    #[arg(long, global = true)]
+    off: bool,
+}
+
+fn lock_command(
+    mut log: ConsoleCommand<LockCommand>,
+    selected: Res<SelectedCells>,
+    grounds: Query<(Entity, &GridCell), With<Ground>>,
+    mut commands: Commands,
+) {
+    if let Some(Ok(LockCommand { selection, row, col, to, off })) = log.take() {
+        let cells: HashMap<IVec2, Entity> = grounds
+            .iter()
+            .map(|(entity, cell)| (cell.coordinates(), entity))
+            .collect();
+
+        let Some(targets) = targets(selection.is_some(), row, col, to, &selected, &cells) else {
+            log.reply("error (selection): no cells are selected.");
+            return;
+        };
+        let mut changed = 0;
+        for coordinates in targets {
+            let Some(entity) = cells.get(&coordinates) else {
+                log.reply(format!("error (cell): no cell at {}, {}.", coordinates.x, coordinates.y));
+                continue;
+            };
+
+            if off {
+                commands.entity(*entity).remove::<Locked>();
+            } else {
+                commands.entity(*entity).insert(Locked);
+            }
+            changed += 1;
+        }
+        log.reply(format!("\t{} {} cells.", if off { "unlocked" } else { "locked" }, changed));
+    }
+}
+
+/// select cells for changing them all at once, or print the selection
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "select", allow_negative_numbers = true)]
+struct SelectCommand {
+    #[arg(requires = "col")]
+    row: Option<i32>,
+    #[arg(requires = "row")]
+    col: Option<i32>,
+    /// opposite corner of a region, as row,col
+    #[arg(long, value_parser = parse_cell, requires = "row")]
+    to: Option<IVec2>,
+    /// add the cells instead of replacing the selection
+    #[arg(long, conflicts_with_all = ["toggle", "remove"])]
+    add: bool,
+    /// flip whether each cell is selected
+    #[arg(long, conflicts_with = "remove")]
+    toggle: bool,
+    /// take the cells out of the selection
+    #[arg(long)]
+    remove: bool,
+    /// select nothing
+    #[arg(long, conflicts_with = "row")]
+    clear: bool,
+}
+
+fn select_command(
+    mut log: ConsoleCommand<SelectCommand>,
+    mut selected: ResMut<SelectedCells>,
+    grounds: Query<(Entity, &GridCell), With<Ground>>,
+) {
+    if let Some(Ok(SelectCommand { row, col, to, add, toggle, remove, clear })) = log.take() {
+        if clear {
+            selected.0.clear();
+        }
+
+        if let (Some(row), Some(col)) = (row, col) {
+            let cells: HashMap<IVec2, Entity> = grounds
+                .iter()
+                .map(|(entity, cell)| (cell.coordinates(), entity))
+                .collect();
+            let mode = match (add, toggle, remove) {
+                (true, _, _) => SelectMode::Add,
+                (_, true, _) => SelectMode::Toggle,
+                (_, _, true) => SelectMode::Remove,
+                _ => SelectMode::Replace,
+            };
+            let picked = region(IVec2::new(row, col), to)
+                .filter_map(|coordinates| cells.get(&coordinates).copied());
+            select::select_cells(&mut selected, picked, mode);
+        }
+
+        let coordinates: Vec<IVec2> = grounds
+            .iter()
+            .filter(|(entity, _)| selected.0.contains(entity))
+            .map(|(_, cell)| cell.coordinates())
+            .collect();
+        let min = coordinates.iter().copied().reduce(IVec2::min);
+        let max = coordinates.iter().copied().reduce(IVec2::max);
+        match min.zip(max) {
+            Some((min, max)) => log.reply(format!(
+                "\t{} cells selected, from {}, {} to {}, {}.",
+                coordinates.len(),
+                min.x,
+                min.y,
+                max.x,
+                max.y
+            )),
+            None => log.reply("\tno cells selected."),
+        }
+    }
+}
+
+/// the selected cells in row order, or the cell and region named, None when nothing is selected
+fn targets(
+    selection: bool,
+    row: Option<i32>,
+    col: Option<i32>,
+    to: Option<IVec2>,
+    selected: &SelectedCells,
+    cells: &HashMap<IVec2, Entity>,
+) -> Option<Vec<IVec2>> {
+    if !selection {
+        let start = IVec2::new(row.unwrap_or_default(), col.unwrap_or_default());
+        return Some(region(start, to).collect());
+    }
+
+    let mut targets: Vec<IVec2> = cells
+        .iter()
+        .filter(|(_, entity)| selected.0.contains(*entity))
+        .map(|(coordinates, _)| *coordinates)
+        .collect();
+    targets.sort_by_key(|coordinates| (coordinates.x, coordinates.y));
+    (!targets.is_empty()).then_some(targets)
+}
+
+/// the cell alone, or every cell in the rectangle up to the opposite corner
+fn region(start: IVec2, to: Option<IVec2>) -> impl Iterator<Item = IVec2> {
+    let end = to.unwrap_or(start);
+    let (min, max) = (start.min(end), start.max(end));
+    (min.x..=max.x).flat_map(move |row| (min.y..=max.y).map(move |col| IVec2::new(row, col)))
+}
+
+/// parses a cell written as row,col
+fn parse_cell(arg: &str) -> Result<IVec2, String> {
+    let Some((row, col)) = arg.split_once(',') else {
+        return Err(format!("expected row,col but got {:?}", arg));
+    };
+
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<i32>()
+            .map_err(|e| format!("{:?} is not a whole number: {}", value, e))
+    };
+    Ok(IVec2::new(parse(row)?, parse(col)?))
+}
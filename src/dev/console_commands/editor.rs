@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+use bevy_console::{AddConsoleCommand, ConsoleCommand};
+use clap::Parser;
+
+use crate::{
+    editor::{EditorState, EditorTool, GameMode, ResizeMap, MAP_SIZE_MAX, MAP_SIZE_MIN},
+    soil::Soil,
+};
+
+pub struct EditorCommandsPlugin;
+
+impl Plugin for EditorCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_console_command::<EditCommand, _>(edit_command)
+            .add_console_command::<PlayCommand, _>(play_command)
+            .add_console_command::<EditorToolCommand, _>(editor_tool_command)
+            .add_console_command::<EditorOptionsCommand, _>(editor_options_command)
+            .add_console_command::<ResizeMapCommand, _>(resize_command);
+    }
+}
+
+/// switch to the level editor
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "edit")]
+struct EditCommand;
+
+fn edit_command(mut log: ConsoleCommand<EditCommand>, mut next_mode: ResMut<NextState<GameMode>>) {
+    if let Some(Ok(EditCommand)) = log.take() {
+        next_mode.set(GameMode::Edit);
+    }
+}
+
+/// play (or play-test) the current level
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "play")]
+struct PlayCommand;
+
+fn play_command(mut log: ConsoleCommand<PlayCommand>, mut next_mode: ResMut<NextState<GameMode>>) {
+    if let Some(Ok(PlayCommand)) = log.take() {
+        next_mode.set(GameMode::Play);
+    }
+}
+
+/// choose the editor tool
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "editor-tool")]
+struct EditorToolCommand {
+    /// height, soil, water, source, sink or goal
+    tool: String,
+}
+
+fn editor_tool_command(mut log: ConsoleCommand<EditorToolCommand>, mut editor: ResMut<EditorState>) {
+    if let Some(Ok(EditorToolCommand { tool })) = log.take() {
+        let Some(tool) = EditorTool::ALL.iter().find(|t| t.name() == tool.to_lowercase()) else {
+            log.reply(format!("error (tool): {:?} is not an editor tool.", tool));
+            return;
+        };
+
+        editor.tool = *tool;
+        editor.goal_start = None;
+    }
+}
+
+/// change the values placed by the editor tools
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "editor-options")]
+struct EditorOptionsCommand {
+    /// soil painted by the soil tool
+    #[arg(long)]
+    soil: Option<String>,
+    /// water added or removed per click
+    #[arg(long)]
+    water_step: Option<f32>,
+    /// water added per second by new sources
+    #[arg(long)]
+    source_rate: Option<f32>,
+    /// water removed per second by new sinks
+    #[arg(long)]
+    sink_rate: Option<f32>,
+    /// water depth required by new goals
+    #[arg(long)]
+    goal_depth: Option<f32>,
+    /// seconds new goals must be held, 0 for none
+    #[arg(long)]
+    goal_duration: Option<f32>,
+}
+
+fn editor_options_command(
+    mut log: ConsoleCommand<EditorOptionsCommand>,
+    mut editor: ResMut<EditorState>,
+) {
+    if let Some(Ok(options)) = log.take() {
+        if let Some(soil) = options.soil {
+            let Some(soil) = Soil::ALL.iter().find(|s| format!("{:?}", s).to_lowercase() == soil.to_lowercase()) else {
+                log.reply(format!("error (soil): {:?} is not a soil.", soil));
+                return;
+            };
+            editor.soil = *soil;
+        }
+        if let Some(step) = options.water_step {
+            editor.water_step = step;
+        }
+        if let Some(rate) = options.source_rate {
+            editor.source_rate = rate;
+        }
+        if let Some(rate) = options.sink_rate {
+            editor.sink_rate = rate;
+        }
+        if let Some(depth) = options.goal_depth {
+            editor.goal_depth = depth;
+        }
+        if let Some(duration) = options.goal_duration {
+            editor.goal_duration = if duration > 0.0 { Some(duration) } else { None };
+        }
+
+        log.reply(format!("{:?}", *editor));
+    }
+}
+
+/// resize the map being edited
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "resize")]
+struct ResizeMapCommand {
+    /// new map size
+    size: i32,
+}
+
+fn resize_command(mut log: ConsoleCommand<ResizeMapCommand>, mut resize: EventWriter<ResizeMap>) {
+    if let Some(Ok(ResizeMapCommand { size })) = log.take() {
+        if !(MAP_SIZE_MIN..=MAP_SIZE_MAX).contains(&size) {
+            log.reply(format!("error (map size): size must be between {} and {}.", MAP_SIZE_MIN, MAP_SIZE_MAX));
+            return;
+        }
+        resize.send(ResizeMap { size });
+    }
+}
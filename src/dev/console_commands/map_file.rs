@@ -1,10 +1,13 @@
-use std::{fs::{self, File}, io::Write};
+use std::{path::Path, time::SystemTime};
 
 use bevy::prelude::*;
 use bevy_console::{AddConsoleCommand, ConsoleCommand};
 use clap::Parser;
 
-use crate::map::{GenerateMap, CurrentMapSettings};
+use crate::{
+    map::{CurrentMapSettings, LoadMap},
+    storage::{self, MapFormat, MAPS_DIRECTORY, MAP_FORMAT_VERSION},
+};
 
 pub struct MapFileCommandsPlugin;
 
@@ -12,7 +15,13 @@ impl Plugin for MapFileCommandsPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_console_command::<SaveMapCommand, _>(save_map_command)
-            .add_console_command::<LoadMapCommand, _>(load_map_command);
+            .add_console_command::<LoadMapCommand, _>(load_map_command)
+            .add_console_command::<UpgradeMapCommand, _>(upgrade_map_command)
+            .add_console_command::<ConvertMapCommand, _>(convert_map_command)
+            .add_console_command::<ListMapsCommand, _>(list_maps_command)
+            .add_console_command::<DeleteMapCommand, _>(delete_map_command)
+            .add_console_command::<RenameMapCommand, _>(rename_map_command)
+            .add_console_command::<MapInfoCommand, _>(map_info_command);
     }
 }
 
@@ -20,27 +29,16 @@ impl Plugin for MapFileCommandsPlugin {
 #[derive(Parser, ConsoleCommand)]
 #[command(name = "save-map")]
 struct SaveMapCommand {
-    /// file name
+    /// file name, ending in .json, .ron or .bin (json by default)
     name: String,
 }
 
 fn save_map_command(mut log: ConsoleCommand<SaveMapCommand>, settings: Res<CurrentMapSettings>) {
     if let Some(Ok(SaveMapCommand { name })) = log.take() {
-        let json_data = match serde_json::to_string_pretty(&settings.value) {
-            Err(e) => panic!("error: failed to serialize map settings: {:?}", e),
-            Ok(data) => data,
-        };
-
-        let path = format!("./assets/maps/{}.json", name);
-        let mut file = match File::create(path) {
-            Err(e) => panic!("error: failed to create file: {:?}", e),
-            Ok(data) => data,
-        };
-
-        match file.write_all(json_data.as_bytes()) {
-            Err(e) => panic!("error: failed to write data to file: {:?}", e),
-            Ok(_) => (),
-        };
+        match storage::save_map(Path::new(MAPS_DIRECTORY), &name, &settings.value) {
+            Err(e) => log.reply(format!("error (save): {}", e)),
+            Ok(path) => log.reply(format!("\tsaved map to {:?}.", path)),
+        }
     }
 }
 
@@ -52,17 +50,223 @@ struct LoadMapCommand {
     name: String,
 }
 
-fn load_map_command(mut log: ConsoleCommand<LoadMapCommand>, mut generate: EventWriter<GenerateMap>) {
+fn load_map_command(mut log: ConsoleCommand<LoadMapCommand>, mut load: EventWriter<LoadMap>) {
     if let Some(Ok(LoadMapCommand { name })) = log.take() {
-        let path = format!("./assets/maps/{}.json", name);
-        let json_data = match fs::read_to_string(path) {
-            Err(e) => panic!("error: failed to read data from file: {:?}", e),
-            Ok(data) => data,
+        //  the asset server loads the map, reading it here first reports what is wrong with it
+        match storage::load_map(Path::new(MAPS_DIRECTORY), &name) {
+            Err(e) => log.reply(format!("error (load): {}", e)),
+            Ok(_) => {
+                log.reply(format!("\tloading map {:?}.", name));
+                load.send(LoadMap { name });
+            }
+        }
+    }
+}
+
+/// rewrite map files in the newest format
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "upgrade-map")]
+struct UpgradeMapCommand {
+    /// file name, every map when left out
+    name: Option<String>,
+}
+
+fn upgrade_map_command(mut log: ConsoleCommand<UpgradeMapCommand>) {
+    if let Some(Ok(UpgradeMapCommand { name })) = log.take() {
+        let directory = Path::new(MAPS_DIRECTORY);
+        let names = match name {
+            Some(name) => vec![name],
+            None => match storage::map_names(directory) {
+                Err(e) => {
+                    log.reply(format!("error (upgrade): {}", e));
+                    return;
+                }
+                Ok(names) => names,
+            },
         };
 
-        match serde_json::from_str(&json_data) {
-            Err(e) => panic!("error: failed to deserialize file data: {:?}", e),
-            Ok(settings) => { generate.send(GenerateMap { settings }); },
+        for name in names {
+            match storage::upgrade_map(directory, &name) {
+                Err(e) => log.reply(format!("error (upgrade): {}", e)),
+                Ok(MAP_FORMAT_VERSION) => log.reply(format!("\t{} is up to date.", name)),
+                Ok(version) => log.reply(format!(
+                    "\tupgraded {} from version {} to {}.",
+                    name, version, MAP_FORMAT_VERSION
+                )),
+            }
+        }
+    }
+}
+
+/// write a copy of a map file in another format
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "convert-map")]
+struct ConvertMapCommand {
+    /// file name
+    name: String,
+    /// json, ron or bin
+    format: String,
+}
+
+fn convert_map_command(mut log: ConsoleCommand<ConvertMapCommand>) {
+    if let Some(Ok(ConvertMapCommand { name, format })) = log.take() {
+        let Some(format) = MapFormat::from_extension(&format) else {
+            log.reply(format!("error (format): {:?} is not a map format.", format));
+            return;
+        };
+
+        match storage::convert_map(Path::new(MAPS_DIRECTORY), &name, format) {
+            Err(e) => log.reply(format!("error (convert): {}", e)),
+            Ok(path) => log.reply(format!("\tconverted {} to {:?}.", name, path)),
+        }
+    }
+}
+
+/// list the map files with their details
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "list-maps")]
+struct ListMapsCommand;
+
+fn list_maps_command(mut log: ConsoleCommand<ListMapsCommand>) {
+    if let Some(Ok(ListMapsCommand)) = log.take() {
+        let directory = Path::new(MAPS_DIRECTORY);
+        let names = match storage::map_names(directory) {
+            Err(e) => {
+                log.reply(format!("error (list): {}", e));
+                return;
+            }
+            Ok(names) => names,
+        };
+
+        for name in names {
+            let info = match storage::map_info(directory, &name) {
+                Err(e) => {
+                    log.reply(format!("\t{} - unreadable: {}", name, e));
+                    continue;
+                }
+                Ok(info) => info,
+            };
+
+            let settings = &info.settings;
+            let modified = info.modified.map(format_age).unwrap_or_default();
+            log.reply(format!(
+                "\t{} - {}x{} {}, modified {}",
+                name,
+                settings.size,
+                settings.size,
+                settings.terrain.name(),
+                modified
+            ));
+
+            let metadata = &settings.metadata;
+            if let Some(title) = &metadata.title {
+                let author = metadata.author.as_deref().unwrap_or("unknown");
+                log.reply(format!("\t\t{} by {}", title, author));
+            }
+            if let Some(description) = &metadata.description {
+                log.reply(format!("\t\t{}", description));
+            }
+            if !metadata.tags.is_empty() {
+                log.reply(format!("\t\ttags: {}", metadata.tags.join(", ")));
+            }
         }
     }
-}
\ No newline at end of file
+}
+
+/// how long ago a file was modified, in the largest whole unit
+fn format_age(time: SystemTime) -> String {
+    let seconds = SystemTime::now()
+        .duration_since(time)
+        .map(|age| age.as_secs())
+        .unwrap_or_default();
+
+    match seconds {
+        0..60 => String::from("just now"),
+        60..3600 => format!("{}m ago", seconds / 60),
+        3600..86400 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
+/// delete a map file
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "delete-map")]
+struct DeleteMapCommand {
+    /// file name
+    name: String,
+}
+
+fn delete_map_command(mut log: ConsoleCommand<DeleteMapCommand>) {
+    if let Some(Ok(DeleteMapCommand { name })) = log.take() {
+        match storage::delete_map(Path::new(MAPS_DIRECTORY), &name) {
+            Err(e) => log.reply(format!("error (delete): {}", e)),
+            Ok(path) => log.reply(format!("\tdeleted {:?}.", path)),
+        }
+    }
+}
+
+/// rename a map file
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "rename-map")]
+struct RenameMapCommand {
+    /// current file name
+    from: String,
+    /// new file name, keeps the format without an extension
+    to: String,
+}
+
+fn rename_map_command(mut log: ConsoleCommand<RenameMapCommand>) {
+    if let Some(Ok(RenameMapCommand { from, to })) = log.take() {
+        match storage::rename_map(Path::new(MAPS_DIRECTORY), &from, &to) {
+            Err(e) => log.reply(format!("error (rename): {}", e)),
+            Ok(path) => log.reply(format!("\trenamed {} to {:?}.", from, path)),
+        }
+    }
+}
+
+/// show or change the current map's title, author, description and tags
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "map-info")]
+struct MapInfoCommand {
+    #[arg(long)]
+    title: Option<String>,
+    #[arg(long)]
+    author: Option<String>,
+    #[arg(long)]
+    description: Option<String>,
+    /// add a tag, may be repeated
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+    /// remove all tags before adding any
+    #[arg(long)]
+    clear_tags: bool,
+}
+
+fn map_info_command(
+    mut log: ConsoleCommand<MapInfoCommand>,
+    mut settings: ResMut<CurrentMapSettings>,
+) {
+    if let Some(Ok(command)) = log.take() {
+        let metadata = &mut settings.value.metadata;
+
+        if command.title.is_some() {
+            metadata.title = command.title;
+        }
+        if command.author.is_some() {
+            metadata.author = command.author;
+        }
+        if command.description.is_some() {
+            metadata.description = command.description;
+        }
+        if command.clear_tags {
+            metadata.tags.clear();
+        }
+        for tag in command.tags {
+            if !metadata.tags.contains(&tag) {
+                metadata.tags.push(tag);
+            }
+        }
+
+        log.reply(format!("{:?}", metadata));
+    }
+}
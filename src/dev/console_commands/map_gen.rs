@@ -1,20 +1,14 @@
-use std::str::{FromStr, Split};
 use bevy::prelude::*;
 use bevy_console::{AddConsoleCommand, ConsoleCommand};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
-use crate::map::{ClearMap, CurvedTerrainSettings, GenerateMap, MapGenerationSettings, TerrainSettings};
-
-const HELP_REPLY: &str = "\tgenerate args:
-\nsize.(i32) - sets the map size
-\nterrain.(type)[.(curve param)] - sets the shape of the map";
-const TERRAIN_HELP_REPLY: &str = "\tterrain args:
-\nflat: for flat terrain
-\ncurved: for curved terrain (curve params):
-\namp: amplitude
-\nwave: wavelength
-\nvert: vertical shift
-\nphase: phase shift";
+use crate::{
+    editor::{MAP_SIZE_MAX, MAP_SIZE_MIN},
+    map::{
+        ClearMap, CurvedTerrainSettings, CustomTerrainSettings, GenerateMap,
+        MapGenerationSettings, ResetLevel, TerrainSettings,
+    },
+};
 
 pub struct MapGenCommandsPlugin;
 
@@ -22,6 +16,7 @@ impl Plugin for MapGenCommandsPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_console_command::<RemoveMapCommand, _>(clear_command)
+            .add_console_command::<ResetLevelCommand, _>(reset_command)
             .add_console_command::<GenerateMapCommand, _>(generate_command);
     }
 }
@@ -40,110 +35,113 @@ fn clear_command(
     }
 }
 
-/// generate map command
+/// reset level command
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "reset")]
+struct ResetLevelCommand;
+
+fn reset_command(
+    mut log: ConsoleCommand<ResetLevelCommand>,
+    mut reset: EventWriter<ResetLevel>
+) {
+    if let Some(Ok(ResetLevelCommand)) = log.take() {
+        reset.send(ResetLevel);
+    }
+}
+
+/// generate a new map, e.g. `generate --size 16 curved --amp 3,4 --wave 0.2,0.1`
 #[derive(Parser, ConsoleCommand)]
-#[command(name = "generate")]
+#[command(name = "generate", allow_negative_numbers = true)]
 struct GenerateMapCommand {
-    /// Some message
-    args: String,
+    /// number of cells along each side
+    #[arg(long, value_parser = clap::value_parser!(i32).range(MAP_SIZE_MIN as i64..=MAP_SIZE_MAX as i64))]
+    size: Option<i32>,
+    /// terrain shape, flat when left out
+    #[command(subcommand)]
+    terrain: Option<TerrainCommand>,
+}
+
+#[derive(Subcommand)]
+enum TerrainCommand {
+    /// every cell at layer 0
+    Flat,
+    /// sine waves along both axes, each value given as x,y
+    Curved {
+        #[arg(long, value_parser = parse_vec2, allow_hyphen_values = true, default_value = "0,0")]
+        amp: Vec2,
+        #[arg(long, value_parser = parse_vec2, allow_hyphen_values = true, default_value = "0,0")]
+        wave: Vec2,
+        #[arg(long, value_parser = parse_vec2, allow_hyphen_values = true, default_value = "0,0")]
+        vert: Vec2,
+        #[arg(long, value_parser = parse_vec2, allow_hyphen_values = true, default_value = "0,0")]
+        phase: Vec2,
+    },
+    /// explicit layers, row by row, one per cell
+    Custom {
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true, required = true)]
+        layers: Vec<i32>,
+    },
 }
 
 fn generate_command(
     mut log: ConsoleCommand<GenerateMapCommand>,
+    mut cleanup: EventWriter<ClearMap>,
     mut generator: EventWriter<GenerateMap>
 ) {
-    if let Some(Ok(GenerateMapCommand { args })) = log.take() {
-        let lowercase = args.to_lowercase();
-        let arg_parts = lowercase.split("|");
-        let mut map_settings = MapGenerationSettings::default(); 
-
-        for part in arg_parts {
-            let mut sub_command = part.split("-");
-
-            match sub_command.next() {
-                Some("help") => {
-                    log.reply(HELP_REPLY);
-                    return;
-                },
-                Some("size") => {
-                    let Some(map_size) = parse_sub_command::<i32>(sub_command.next()) else {
-                        log.reply("error (map size): could not parse the size provided - please use i32.");
-                        continue;
-                    };
-                    map_settings.size = map_size;
-                },
-                Some("terrain") => {
-                    let Some(terrain_type) = sub_command.next() else {
-                        log.reply(format!("error (terrain type): no terrain type provided."));
-                        return;
-                    };
+    if let Some(Ok(GenerateMapCommand { size, terrain })) = log.take() {
+        let mut map_settings = MapGenerationSettings::default();
+        if let Some(size) = size {
+            map_settings.size = size;
+        }
 
-                    match terrain_type {
-                        "flat" => map_settings.terrain = TerrainSettings::FLAT,
-                        "curved" => map_settings.terrain = TerrainSettings::CURVED(parse_curved_terrain_args(&mut sub_command)),
-                        "help" => log.reply(TERRAIN_HELP_REPLY),
-                        _ => {
-                            log.reply("error (terrain type): terrain type not recognized.");
-                            return;
-                        },
-                    };
-                    log.reply(format!("\tgenerating {:?} terrain.", terrain_type));
-                },
-                Some(p) => {
-                    log.reply(format!("error (args): {:?} does not match any known arg.", p));
-                    return;
-                },
-                None => {
-                    log.reply(format!("error (args): cannot could not comprehend arg."));
+        map_settings.terrain = match terrain.unwrap_or(TerrainCommand::Flat) {
+            TerrainCommand::Flat => TerrainSettings::FLAT,
+            TerrainCommand::Curved { amp, wave, vert, phase } => {
+                TerrainSettings::CURVED(CurvedTerrainSettings {
+                    amplitude: amp,
+                    wavelength: wave,
+                    vertical_shift: vert,
+                    phase_shift: phase,
+                })
+            }
+            TerrainCommand::Custom { layers } => {
+                let cells = (map_settings.size * map_settings.size) as usize;
+                if layers.len() != cells {
+                    log.reply(format!(
+                        "error (layers): a {0}x{0} map needs {1} layers, got {2}.",
+                        map_settings.size,
+                        cells,
+                        layers.len()
+                    ));
                     return;
-                } 
+                }
+                TerrainSettings::CUSTOM(CustomTerrainSettings { layers })
             }
-        }
+        };
 
+        log.reply(format!(
+            "\tgenerating {0}x{0} {1} terrain.",
+            map_settings.size,
+            map_settings.terrain.name()
+        ));
+        cleanup.send(ClearMap);
         generator.send(GenerateMap {
             settings: map_settings,
         });
     }
 }
 
-fn parse_curved_terrain_args(sub_command: &mut Split<'_, &str>) -> CurvedTerrainSettings {
-    let mut settings = CurvedTerrainSettings::default(); 
-
-    loop {
-        let Some(sub) = sub_command.next() else {
-            break;
-        };
-
-        let Some(x_val) = parse_sub_command::<f32>(sub_command.next()) else {
-            sub_command.next();
-            break;
-        };
-    
-        let Some(y_val) = parse_sub_command::<f32>(sub_command.next()) else {
-            break;
-        };
-        let vec = Vec2::new(x_val, y_val);
-    
-        match sub {
-            "amp" => settings.amplitude = vec,
-            "wave" => settings.wavelength = vec,
-            "vert" => settings.vertical_shift = vec,
-            "phase" => settings.phase_shift = vec,
-            _ => ()
-        }
-    }
-
-    return settings;
-}
-
-fn parse_sub_command<T>(option: Option<&str>) -> Option<T>
-where T: FromStr 
-{
-    let Some(str) = option else {
-        return None;
+/// parses a pair of numbers written as x,y
+fn parse_vec2(arg: &str) -> Result<Vec2, String> {
+    let Some((x, y)) = arg.split_once(',') else {
+        return Err(format!("expected x,y but got {:?}", arg));
     };
-    let Ok(val) = str.parse::<T>() else {
-        return None;
+
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<f32>()
+            .map_err(|e| format!("{:?} is not a number: {}", value, e))
     };
-    return Some(val);
+    Ok(Vec2::new(parse(x)?, parse(y)?))
 }
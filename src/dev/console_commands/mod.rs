@@ -1,15 +1,52 @@
+mod bindings;
+mod brush;
+mod campaign;
+mod cell;
+mod editor;
 mod map_file;
 mod map_gen;
+mod overlays;
+mod path;
+mod script;
+mod stamp;
+mod stats;
+mod tuning;
 
 use bevy::prelude::*;
 use bevy_console::ConsolePlugin;
+use bindings::BindingsCommandsPlugin;
+use brush::BrushCommandsPlugin;
+use campaign::CampaignCommandsPlugin;
+use cell::CellCommandsPlugin;
+use editor::EditorCommandsPlugin;
 use map_file::MapFileCommandsPlugin;
 use map_gen::MapGenCommandsPlugin;
+use overlays::OverlayCommandsPlugin;
+use path::PathCommandsPlugin;
+use script::ScriptCommandsPlugin;
+use stamp::StampCommandsPlugin;
+use stats::StatsCommandsPlugin;
+use tuning::TuningCommandsPlugin;
 
 pub struct ConComPlugin;
 
 impl Plugin for ConComPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((ConsolePlugin, MapGenCommandsPlugin, MapFileCommandsPlugin));
+        app.add_plugins((
+            ConsolePlugin,
+            MapGenCommandsPlugin,
+            MapFileCommandsPlugin,
+            CampaignCommandsPlugin,
+            EditorCommandsPlugin,
+            CellCommandsPlugin,
+            ScriptCommandsPlugin,
+            TuningCommandsPlugin,
+            StatsCommandsPlugin,
+            OverlayCommandsPlugin,
+            BrushCommandsPlugin,
+            PathCommandsPlugin,
+            StampCommandsPlugin,
+            BindingsCommandsPlugin,
+        ));
     }
 }
@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+use bevy_console::{AddConsoleCommand, ConsoleCommand};
+use clap::Parser;
+
+use crate::dev::overlays::{DebugOverlays, Overlay};
+
+pub struct OverlayCommandsPlugin;
+
+impl Plugin for OverlayCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_console_command::<OverlayCommand, _>(overlay_command);
+    }
+}
+
+/// show or hide a debug overlay
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "overlay")]
+struct OverlayCommand {
+    /// flow, links, depth or layers, lists the overlays when omitted
+    name: Option<String>,
+}
+
+fn overlay_command(mut log: ConsoleCommand<OverlayCommand>, mut overlays: ResMut<DebugOverlays>) {
+    if let Some(Ok(OverlayCommand { name })) = log.take() {
+        let Some(name) = name else {
+            for overlay in Overlay::ALL {
+                let state = if overlays.enabled(overlay) {
+                    "on"
+                } else {
+                    "off"
+                };
+                log.reply(format!("\t{}: {}", overlay.name(), state));
+            }
+            return;
+        };
+
+        let Some(overlay) = Overlay::from_name(&name) else {
+            log.reply(format!("error (overlay): unknown overlay {:?}.", name));
+            return;
+        };
+
+        let state = if overlays.toggle(overlay) {
+            "on"
+        } else {
+            "off"
+        };
+        log.reply(format!("\t{} overlay {}.", overlay.name(), state));
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use bevy_console::{AddConsoleCommand, ConsoleCommand};
+use clap::Parser;
+
+use crate::tools::path::{PathProfile, PathRoute, PathSettings};
+
+pub struct PathCommandsPlugin;
+
+impl Plugin for PathCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_console_command::<PathCommand, _>(path_command);
+    }
+}
+
+/// change how the path tool digs, or print it
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "path")]
+struct PathCommand {
+    /// straight or least-cost
+    #[arg(long)]
+    route: Option<String>,
+    /// depth, following the ground, or gradient, an even slope between the ends
+    #[arg(long)]
+    profile: Option<String>,
+    /// layers to dig, or to raise with the right button
+    #[arg(long, value_parser = clap::value_parser!(i32).range(0..))]
+    depth: Option<i32>,
+}
+
+fn path_command(mut log: ConsoleCommand<PathCommand>, mut settings: ResMut<PathSettings>) {
+    if let Some(Ok(PathCommand {
+        route,
+        profile,
+        depth,
+    })) = log.take()
+    {
+        if let Some(route) = route {
+            let Some(route) = PathRoute::from_name(&route) else {
+                log.reply(format!("error (path): unknown route {:?}.", route));
+                return;
+            };
+            settings.route = route;
+        }
+        if let Some(profile) = profile {
+            let Some(profile) = PathProfile::from_name(&profile) else {
+                log.reply(format!("error (path): unknown profile {:?}.", profile));
+                return;
+            };
+            settings.profile = profile;
+        }
+        if let Some(depth) = depth {
+            settings.depth = depth;
+        }
+
+        log.reply(format!("\t{}.", settings.describe()));
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use bevy_console::{AddConsoleCommand, ConsoleCommand, ConsoleCommandEntered, PrintConsoleLine};
+use clap::Parser;
+
+use crate::{editor::GameMode, LaunchOptions};
+
+const SCRIPTS_DIRECTORY: &str = "./assets/scripts";
+const WAIT_COMMAND: &str = "wait";
+const EXEC_COMMAND: &str = "exec";
+/// How deeply scripts may exec other scripts
+const EXEC_DEPTH_MAX: usize = 8;
+
+pub struct ScriptCommandsPlugin;
+
+impl Plugin for ScriptCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ScriptQueue::default());
+
+        app.add_console_command::<ExecCommand, _>(exec_command)
+            .add_systems(Startup, run_startup_script)
+            .add_systems(Update, run_script);
+    }
+}
+
+/// Script lines waiting to run
+#[derive(Resource, Debug, Default)]
+struct ScriptQueue {
+    lines: VecDeque<ScriptLine>,
+    /// simulation ticks left before the next line runs
+    wait: u32,
+}
+
+#[derive(Debug)]
+enum ScriptLine {
+    Command { text: String, args: Vec<String> },
+    Wait(u32),
+}
+
+impl ScriptQueue {
+    /// Runs a script before anything already queued
+    fn push_front(&mut self, lines: Vec<ScriptLine>) {
+        for line in lines.into_iter().rev() {
+            self.lines.push_front(line);
+        }
+    }
+}
+
+/// run a file of console commands, one per line
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "exec")]
+struct ExecCommand {
+    /// script path, or a file in assets/scripts
+    file: PathBuf,
+}
+
+fn exec_command(mut log: ConsoleCommand<ExecCommand>, mut queue: ResMut<ScriptQueue>) {
+    if let Some(Ok(ExecCommand { file })) = log.take() {
+        match read_script(&file, &mut Vec::new()) {
+            Err(e) => log.reply(format!("error (exec): {}", e)),
+            Ok(lines) => {
+                log.reply(format!("\trunning {} lines from {:?}.", lines.len(), file));
+                queue.push_front(lines);
+            }
+        }
+    }
+}
+
+fn run_startup_script(options: Option<Res<LaunchOptions>>, mut queue: ResMut<ScriptQueue>) {
+    let Some(file) = options.and_then(|options| options.script.clone()) else {
+        return;
+    };
+
+    match read_script(&file, &mut Vec::new()) {
+        Err(e) => error!("failed to read startup script: {}", e),
+        Ok(lines) => queue.push_front(lines),
+    }
+}
+
+/// Runs one line per frame, so each command sees the effects of the last
+fn run_script(
+    mut queue: ResMut<ScriptQueue>,
+    mode: Res<State<GameMode>>,
+    mut entered: EventWriter<ConsoleCommandEntered>,
+    mut print: EventWriter<PrintConsoleLine>,
+) {
+    if queue.wait > 0 {
+        //  the simulation only ticks while playing
+        if *mode.get() == GameMode::Play {
+            queue.wait -= 1;
+        }
+        return;
+    }
+
+    match queue.lines.pop_front() {
+        Some(ScriptLine::Wait(ticks)) => queue.wait = ticks,
+        Some(ScriptLine::Command { text, mut args }) => {
+            print.send(PrintConsoleLine::new(format!("$ {}", text)));
+            let command_name = args.remove(0);
+            entered.send(ConsoleCommandEntered { command_name, args });
+        }
+        None => (),
+    }
+}
+
+/// Reads a script, skipping blank lines and `#` comments
+///
+/// Scripts it execs are read in place of the `exec` line, so their lines run before the rest
+/// of this one. `running` holds the scripts being read, to catch a script that execs itself.
+fn read_script(file: &Path, running: &mut Vec<PathBuf>) -> Result<Vec<ScriptLine>, String> {
+    let path = if file.exists() {
+        file.to_path_buf()
+    } else {
+        Path::new(SCRIPTS_DIRECTORY).join(file)
+    };
+    let text = fs::read_to_string(&path).map_err(|e| format!("{:?}: {}", path, e))?;
+
+    let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+    if running.contains(&canonical) {
+        return Err(format!("{:?} execs itself", path));
+    }
+    if running.len() >= EXEC_DEPTH_MAX {
+        return Err(format!(
+            "{:?} is nested more than {} scripts deep",
+            path, EXEC_DEPTH_MAX
+        ));
+    }
+    running.push(canonical);
+
+    let mut lines = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some(args) = shlex::split(line).filter(|args| !args.is_empty()) else {
+            return Err(format!("{:?} line {}: unmatched quotes", path, index + 1));
+        };
+
+        if args[0] == WAIT_COMMAND {
+            let ticks = match args.get(1).map(|ticks| ticks.parse::<u32>()) {
+                Some(Ok(ticks)) if args.len() == 2 => ticks,
+                _ => {
+                    return Err(format!(
+                        "{:?} line {}: expected `wait <ticks>`",
+                        path,
+                        index + 1
+                    ))
+                }
+            };
+            lines.push(ScriptLine::Wait(ticks));
+        } else if args[0] == EXEC_COMMAND {
+            let [_, nested] = args.as_slice() else {
+                return Err(format!(
+                    "{:?} line {}: expected `exec <file>`",
+                    path,
+                    index + 1
+                ));
+            };
+            lines.extend(read_script(Path::new(nested), running)?);
+        } else {
+            lines.push(ScriptLine::Command {
+                text: line.to_string(),
+                args,
+            });
+        }
+    }
+
+    running.pop();
+    Ok(lines)
+}
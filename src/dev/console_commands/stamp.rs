@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_console::{AddConsoleCommand, ConsoleCommand};
+use clap::Parser;
+
+use crate::{
+    storage::{self, STAMPS_DIRECTORY},
+    tools::stamp::{Clipboard, PasteSettings, StampBlend},
+};
+
+pub struct StampCommandsPlugin;
+
+impl Plugin for StampCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_console_command::<StampCommand, _>(stamp_command)
+            .add_console_command::<SaveStampCommand, _>(save_stamp_command)
+            .add_console_command::<LoadStampCommand, _>(load_stamp_command)
+            .add_console_command::<ListStampsCommand, _>(list_stamps_command);
+    }
+}
+
+/// change how the clipboard is pasted, or print it
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "stamp")]
+struct StampCommand {
+    /// replace, add or max
+    #[arg(long)]
+    blend: Option<String>,
+    /// quarter turns counterclockwise
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..4))]
+    turns: Option<u8>,
+    /// flip the columns before turning
+    #[arg(long)]
+    mirror: Option<bool>,
+}
+
+fn stamp_command(mut log: ConsoleCommand<StampCommand>, mut settings: ResMut<PasteSettings>) {
+    if let Some(Ok(StampCommand { blend, turns, mirror })) = log.take() {
+        if let Some(blend) = blend {
+            let Some(blend) = StampBlend::from_name(&blend) else {
+                log.reply(format!("error (stamp): unknown blend {:?}.", blend));
+                return;
+            };
+            settings.blend = blend;
+        }
+        if let Some(turns) = turns {
+            settings.turns = turns;
+        }
+        if let Some(mirror) = mirror {
+            settings.mirror = mirror;
+        }
+
+        log.reply(format!("\t{}.", settings.describe()));
+    }
+}
+
+/// save the clipboard as a stamp file
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "save-stamp")]
+struct SaveStampCommand {
+    /// file name
+    name: String,
+}
+
+fn save_stamp_command(mut log: ConsoleCommand<SaveStampCommand>, clipboard: Res<Clipboard>) {
+    if let Some(Ok(SaveStampCommand { name })) = log.take() {
+        let Some(stamp) = &clipboard.0 else {
+            log.reply("error (save): the clipboard is empty, copy a region first.");
+            return;
+        };
+
+        match storage::save_stamp(Path::new(STAMPS_DIRECTORY), &name, stamp) {
+            Err(e) => log.reply(format!("error (save): {}", e)),
+            Ok(path) => log.reply(format!("\tsaved stamp to {:?}.", path)),
+        }
+    }
+}
+
+/// put a stamp file on the clipboard, ready to paste
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "load-stamp")]
+struct LoadStampCommand {
+    /// file name
+    name: String,
+}
+
+fn load_stamp_command(mut log: ConsoleCommand<LoadStampCommand>, mut clipboard: ResMut<Clipboard>) {
+    if let Some(Ok(LoadStampCommand { name })) = log.take() {
+        match storage::load_stamp(Path::new(STAMPS_DIRECTORY), &name) {
+            Err(e) => log.reply(format!("error (load): {}", e)),
+            Ok(stamp) => {
+                log.reply(format!("\tloaded stamp {:?} of {} cells.", name, stamp.cells.len()));
+                clipboard.0 = Some(stamp);
+            }
+        }
+    }
+}
+
+/// list the stamp files
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "list-stamps")]
+struct ListStampsCommand;
+
+fn list_stamps_command(mut log: ConsoleCommand<ListStampsCommand>) {
+    if let Some(Ok(ListStampsCommand)) = log.take() {
+        match storage::stamp_names(Path::new(STAMPS_DIRECTORY)) {
+            Err(e) => log.reply(format!("error (list): {}", e)),
+            Ok(names) if names.is_empty() => log.reply("\tno stamps saved."),
+            Ok(names) => {
+                for name in names {
+                    log.reply(format!("\t{}", name));
+                }
+            }
+        }
+    }
+}
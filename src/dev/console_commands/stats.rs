@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use bevy_console::{AddConsoleCommand, ConsoleCommand, PrintConsoleLine};
+use clap::Parser;
+
+use crate::{
+    dev::stats::{SimulationStats, StatsPanel},
+    equilibrium::OscillationDetected,
+};
+
+pub struct StatsCommandsPlugin;
+
+impl Plugin for StatsCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_console_command::<StatsCommand, _>(stats_command)
+            .add_systems(Update, print_oscillation);
+    }
+}
+
+/// print water and ground statistics
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "stats")]
+struct StatsCommand {
+    /// show or hide the on-screen panel instead
+    #[arg(long)]
+    panel: bool,
+}
+
+fn stats_command(
+    mut log: ConsoleCommand<StatsCommand>,
+    stats: Res<SimulationStats>,
+    mut panel: ResMut<StatsPanel>,
+) {
+    if let Some(Ok(StatsCommand { panel: toggle })) = log.take() {
+        if toggle {
+            panel.visible = !panel.visible;
+            let state = if panel.visible { "shown" } else { "hidden" };
+            log.reply(format!("\tstats panel {}.", state));
+            return;
+        }
+
+        for line in stats.lines() {
+            log.reply(format!("\t{}", line));
+        }
+    }
+}
+
+/// Names the sloshing cells, so they can be inspected with `cell`
+fn print_oscillation(
+    mut event: EventReader<OscillationDetected>,
+    mut print: EventWriter<PrintConsoleLine>,
+) {
+    for oscillation in event.read() {
+        let cells: Vec<String> = oscillation
+            .cells
+            .iter()
+            .map(|cell| format!("{}, {}", cell.x, cell.y))
+            .collect();
+        print.send(PrintConsoleLine::new(format!(
+            "warning (water): oscillating in {}",
+            cells.join("; ")
+        )));
+    }
+}
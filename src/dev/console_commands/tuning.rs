@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use bevy_console::{AddConsoleCommand, ConsoleCommand};
+use clap::Parser;
+use serde_json::Value;
+
+use crate::{
+    map::{ClearMap, CurrentMapSettings, GenerateMap},
+    tuning::{BaseTuning, CameraTuning, Tuning, TuningConfig},
+};
+
+pub struct TuningCommandsPlugin;
+
+impl Plugin for TuningCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_console_command::<GetCommand, _>(get_command)
+            .add_console_command::<SetCommand, _>(set_command);
+    }
+}
+
+/// print tuning values, e.g. `get simulation.shift_rate`
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "get")]
+struct GetCommand {
+    /// dotted value name, all values when omitted
+    key: Option<String>,
+}
+
+fn get_command(
+    mut log: ConsoleCommand<GetCommand>,
+    tuning: Res<Tuning>,
+    camera: Res<CameraTuning>,
+) {
+    if let Some(Ok(GetCommand { key })) = log.take() {
+        let config = TuningConfig {
+            simulation: tuning.clone(),
+            camera: camera.clone(),
+        };
+
+        let Some(key) = key else {
+            for group in ["simulation", "camera"] {
+                let Some(Value::Object(values)) = config.get(group) else {
+                    continue;
+                };
+                for (name, value) in values {
+                    log.reply(format!("\t{}.{} = {}", group, name, value));
+                }
+            }
+            return;
+        };
+
+        match config.get(&key) {
+            None => log.reply(format!("error (tuning): {:?} is not a tuning value.", key)),
+            Some(value) => log.reply(format!("\t{} = {}", key, value)),
+        }
+    }
+}
+
+/// change a tuning value until the game closes, e.g. `set simulation.shift_rate 12`
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "set", allow_negative_numbers = true)]
+struct SetCommand {
+    /// dotted value name
+    key: String,
+    value: String,
+}
+
+fn set_command(
+    mut log: ConsoleCommand<SetCommand>,
+    mut tuning: ResMut<Tuning>,
+    mut base: ResMut<BaseTuning>,
+    mut camera: ResMut<CameraTuning>,
+    settings: Res<CurrentMapSettings>,
+    mut cleanup: EventWriter<ClearMap>,
+    mut generator: EventWriter<GenerateMap>,
+) {
+    if let Some(Ok(SetCommand { key, value })) = log.take() {
+        //  anything that is not json is taken as a string
+        let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+
+        let mut config = TuningConfig {
+            simulation: tuning.clone(),
+            camera: camera.clone(),
+        };
+        if let Err(e) = config.set(&key, value.clone()) {
+            log.reply(format!("error (tuning): {}", e));
+            return;
+        }
+
+        //  the base keeps the value when the next map is generated
+        let mut base_config = TuningConfig {
+            simulation: base.0.clone(),
+            camera: camera.clone(),
+        };
+        if base_config.set(&key, value).is_ok() {
+            base.0 = base_config.simulation;
+        }
+
+        log.reply(format!("\t{} = {}", key, config.get(&key).unwrap_or_default()));
+
+        //  cells keep the heights they were built with, so the level is built again
+        if config.simulation.cell_height != tuning.cell_height {
+            log.reply("\trebuilding the level for the new cell height.");
+            cleanup.send(ClearMap);
+            generator.send(GenerateMap {
+                settings: settings.value.clone(),
+            });
+        }
+
+        *tuning = config.simulation;
+        *camera = config.camera;
+    }
+}
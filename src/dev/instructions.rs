@@ -1,24 +1,17 @@
 use bevy::prelude::*;
 
-use super::user_testing::{WaterToggle, WaterToggled};
-
 pub struct InstructionsPlugin;
 
 impl Plugin for InstructionsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<WaterToggled>();
-        app.add_systems(Startup, setup)
-            .add_systems(Update, toggle_water_display);
+        app.add_systems(Startup, setup);
     }
 }
 
-#[derive(Component)]
-struct WaterToggleText;
-
-fn setup(mut commands: Commands, water_toggle: Res<WaterToggle>) {
+fn setup(mut commands: Commands) {
     // Text to describe the controls.
     commands.spawn((
-        Text::new("Left click a block to pull it up; right click a block to push it down."),
+        Text::new("Pick a tool with 0-9, C, V or the toolbar. Left click a block to use it, or drag across blocks; right click to do the opposite. WASD or the arrow keys pan the camera. X or the d-pad puts a cursor on the map, moved with the arrow keys or d-pad, with Enter or A to use the tool, Backspace or B for its opposite, and LB and RB to change tools. [ and ] size the brush, , and . set its strength, B changes its shape, Ctrl+Z undoes. T turns a paste, M mirrors it and G changes how it blends. Drag with the select tool, holding Shift or LT to add or Ctrl or RT to toggle; PageUp and PageDown raise and lower the selection, H levels it, F fills it with water, K locks it and Esc clears it. Press R to reset the level, L to select a level, E to edit it, and F1 to change the controls."),
         Node {
             position_type: PositionType::Absolute,
             top: Val::Px(12.0),
@@ -26,28 +19,4 @@ fn setup(mut commands: Commands, water_toggle: Res<WaterToggle>) {
             ..default()
         },
     ));
-
-    // Text to describe the water toggle
-    commands.spawn((
-        Text::new(format!("Water toggled: {:?}", water_toggle.0)),
-        WaterToggleText,
-        Node {
-            position_type: PositionType::Absolute,
-            top: Val::Px(36.0),
-            left: Val::Px(12.0),
-            ..default()
-        },
-    ));
-}
-
-fn toggle_water_display(
-    mut toggled: EventReader<WaterToggled>,
-    toggle: Res<WaterToggle>,
-    mut query: Query<&mut Text, With<WaterToggleText>>,
-) {
-    for _ in toggled.read() {
-        if let Ok(mut text) = query.get_single_mut() {
-            **text = format!("Water toggled: {:?}", toggle.0);
-        };
-    }
 }
@@ -1,16 +1,23 @@
 mod console_commands;
 mod instructions;
-pub mod user_testing;
+pub mod overlays;
+pub mod stats;
 
 use bevy::prelude::*;
 use console_commands::ConComPlugin;
 use instructions::InstructionsPlugin;
-use user_testing::UserTestingPlugin;
+use overlays::OverlaysPlugin;
+use stats::StatsPlugin;
 
 pub struct DevPlugin;
 
 impl Plugin for DevPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((ConComPlugin, InstructionsPlugin, UserTestingPlugin));
+        app.add_plugins((
+            ConComPlugin,
+            InstructionsPlugin,
+            StatsPlugin,
+            OverlaysPlugin,
+        ));
     }
 }
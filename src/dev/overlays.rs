@@ -0,0 +1,302 @@
+use std::{collections::HashSet, f32::consts::FRAC_PI_2};
+
+use bevy::prelude::*;
+
+use crate::{
+    bindings::input_unclaimed,
+    fluid_dynamics::Draining,
+    flying_camera::{CameraRoot, FlyingCamera},
+    grid::GridCell,
+    ground::Ground,
+    neighborhood::Neighborhood,
+    tuning::Tuning,
+    water::Water,
+};
+
+const OVERLAY_KEYS: [(KeyCode, Overlay); 4] = [
+    (KeyCode::F5, Overlay::Flow),
+    (KeyCode::F6, Overlay::Links),
+    (KeyCode::F7, Overlay::Depth),
+    (KeyCode::F8, Overlay::Layers),
+];
+
+const LINK_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.3);
+const BROKEN_LINK_COLOR: Color = Color::srgb(1.0, 0.0, 0.0);
+const FILLING_COLOR: Color = Color::srgb(0.0, 1.0, 0.3);
+const EMPTYING_COLOR: Color = Color::srgb(1.0, 0.5, 0.0);
+const SHALLOW_COLOR: LinearRgba = LinearRgba::rgb(0.6, 1.0, 1.0);
+const DEEP_COLOR: LinearRgba = LinearRgba::rgb(0.1, 0.0, 0.5);
+
+/// half a cube, from a cell's center to its top face
+const CELL_TOP: f32 = 0.5;
+
+/// Large maps only label the cells nearest the camera, each label is a UI node
+const LAYER_LABELS_MAX: usize = 1024;
+
+pub struct OverlaysPlugin;
+
+impl Plugin for OverlaysPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DebugOverlays::default());
+
+        app.add_systems(
+            Update,
+            (
+                overlay_keys.run_if(input_unclaimed),
+                draw_flow.run_if(overlay_enabled(Overlay::Flow)),
+                draw_links.run_if(overlay_enabled(Overlay::Links)),
+                draw_depth.run_if(overlay_enabled(Overlay::Depth)),
+                (spawn_layer_labels, update_layer_labels).chain(),
+            ),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overlay {
+    /// arrows for the direction water flows and each cell's drain rate
+    Flow,
+    /// lines between neighbors, red where a link is broken
+    Links,
+    /// a colour scale of each cell's water amount
+    Depth,
+    /// each ground cell's layer
+    Layers,
+}
+
+impl Overlay {
+    pub const ALL: [Overlay; 4] = [
+        Overlay::Flow,
+        Overlay::Links,
+        Overlay::Depth,
+        Overlay::Layers,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Overlay::Flow => "flow",
+            Overlay::Links => "links",
+            Overlay::Depth => "depth",
+            Overlay::Layers => "layers",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|overlay| overlay.name() == name)
+    }
+}
+
+/// The debug overlays being drawn
+#[derive(Resource, Debug, Default)]
+pub struct DebugOverlays {
+    pub flow: bool,
+    pub links: bool,
+    pub depth: bool,
+    pub layers: bool,
+}
+
+impl DebugOverlays {
+    pub fn enabled(&self, overlay: Overlay) -> bool {
+        match overlay {
+            Overlay::Flow => self.flow,
+            Overlay::Links => self.links,
+            Overlay::Depth => self.depth,
+            Overlay::Layers => self.layers,
+        }
+    }
+
+    /// Flips an overlay, returning whether it is now shown
+    pub fn toggle(&mut self, overlay: Overlay) -> bool {
+        let enabled = match overlay {
+            Overlay::Flow => &mut self.flow,
+            Overlay::Links => &mut self.links,
+            Overlay::Depth => &mut self.depth,
+            Overlay::Layers => &mut self.layers,
+        };
+        *enabled = !*enabled;
+        *enabled
+    }
+}
+
+fn overlay_enabled(overlay: Overlay) -> impl Fn(Res<DebugOverlays>) -> bool {
+    move |overlays| overlays.enabled(overlay)
+}
+
+/// Labels a ground cell with its layer
+#[derive(Component)]
+struct LayerLabel {
+    ground: Entity,
+}
+
+fn overlay_keys(keys: Res<ButtonInput<KeyCode>>, mut overlays: ResMut<DebugOverlays>) {
+    for (key, overlay) in OVERLAY_KEYS.iter() {
+        if keys.just_pressed(*key) {
+            overlays.toggle(*overlay);
+        }
+    }
+}
+
+fn draw_flow(
+    mut gizmos: Gizmos,
+    tuning: Res<Tuning>,
+    waters: Query<(&GlobalTransform, &Water, &Neighborhood, Option<&Draining>)>,
+    neighbors: Query<&GlobalTransform, With<Water>>,
+) {
+    for (transform, water, neighborhood, draining) in waters.iter() {
+        let position = transform.translation();
+        let top = position + Vec3::Y * CELL_TOP;
+
+        //  the drain rate, upward while the cell fills
+        if let Some(draining) = draining.filter(|draining| draining.rate != 0.0) {
+            let color = if draining.rate > 0.0 {
+                FILLING_COLOR
+            } else {
+                EMPTYING_COLOR
+            };
+            gizmos.arrow(top, top + Vec3::Y * draining.rate * 0.5, color);
+        }
+
+        if water.amount <= 0.0 {
+            continue;
+        }
+
+        //  water leaves toward every neighbor with a lower surface, as in set_drain_rate
+        let outflow: Vec3 = neighborhood
+            .get_neighbors()
+            .iter()
+            .filter_map(|entity| neighbors.get(*entity).ok())
+            .map(|neighbor| neighbor.translation())
+            .filter(|neighbor| position.y - neighbor.y >= tuning.level_cutoff)
+            .map(|neighbor| (neighbor - position).with_y(0.0) * (position.y - neighbor.y))
+            .sum();
+        if outflow != Vec3::ZERO {
+            gizmos.arrow(top, top + outflow.clamp_length_max(1.0), EMPTYING_COLOR);
+        }
+    }
+}
+
+fn draw_links(
+    mut gizmos: Gizmos,
+    //  ground and water cells both have neighborhoods
+    cells: Query<(Entity, &GlobalTransform, &Neighborhood)>,
+) {
+    for (entity, transform, neighborhood) in cells.iter() {
+        let top = transform.translation() + Vec3::Y * CELL_TOP;
+
+        for neighbor_entity in neighborhood.get_neighbors() {
+            //  the map edge has no neighbor
+            if neighbor_entity == Entity::PLACEHOLDER {
+                continue;
+            }
+
+            //  a link is broken when its neighbor is gone or does not link back
+            match cells.get(neighbor_entity) {
+                Ok((_, neighbor_transform, neighbor_neighborhood)) => {
+                    let neighbor_top = neighbor_transform.translation() + Vec3::Y * CELL_TOP;
+                    let color = if neighbor_neighborhood.get_neighbors().contains(&entity) {
+                        LINK_COLOR
+                    } else {
+                        BROKEN_LINK_COLOR
+                    };
+                    //  each cell draws its half, so one-way links show which side is wrong
+                    gizmos.line(top, top.lerp(neighbor_top, 0.5), color);
+                }
+                Err(_) => {
+                    gizmos.sphere(top, 0.1, BROKEN_LINK_COLOR);
+                }
+            }
+        }
+    }
+}
+
+fn draw_depth(mut gizmos: Gizmos, tuning: Res<Tuning>, waters: Query<(&GlobalTransform, &Water)>) {
+    let deepest = waters
+        .iter()
+        .map(|(_, water)| water.amount)
+        .fold(tuning.cell_height, f32::max);
+
+    for (transform, water) in waters.iter().filter(|(_, water)| water.amount > 0.0) {
+        let color = SHALLOW_COLOR.mix(&DEEP_COLOR, water.amount / deepest);
+        let top = transform.translation() + Vec3::Y * CELL_TOP;
+        gizmos.rect(
+            Isometry3d::new(top, Quat::from_rotation_x(FRAC_PI_2)),
+            Vec2::splat(0.9),
+            color,
+        );
+    }
+}
+
+/// Adds a label to the ground cells while the overlay is shown, and removes them after
+fn spawn_layer_labels(
+    overlays: Res<DebugOverlays>,
+    grounds: Query<(Entity, &GlobalTransform), With<Ground>>,
+    added: Query<(), Added<Ground>>,
+    labels: Query<(Entity, &LayerLabel)>,
+    root: Single<&GlobalTransform, With<CameraRoot>>,
+    mut commands: Commands,
+) {
+    //  labels only change when the overlay is toggled or a new map is built
+    if !overlays.is_changed() && added.is_empty() {
+        return;
+    }
+
+    if !overlays.layers {
+        for (label, _) in labels.iter() {
+            commands.entity(label).despawn_recursive();
+        }
+        return;
+    }
+
+    //  maps are regenerated, so labels follow whichever cells exist
+    for (label, layer_label) in labels.iter() {
+        if grounds.get(layer_label.ground).is_err() {
+            commands.entity(label).despawn_recursive();
+        }
+    }
+    let labeled: HashSet<Entity> = labels.iter().map(|(_, label)| label.ground).collect();
+    let mut unlabeled: Vec<(Entity, f32)> = grounds
+        .iter()
+        .filter(|(ground, _)| !labeled.contains(ground))
+        .map(|(ground, transform)| {
+            let distance = transform.translation().distance_squared(root.translation());
+            (ground, distance)
+        })
+        .collect();
+    unlabeled.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let room = LAYER_LABELS_MAX.saturating_sub(labeled.len());
+    for (ground, _) in unlabeled.into_iter().take(room) {
+        commands.spawn((
+            Text::new(""),
+            TextFont::from_font_size(12.0),
+            LayerLabel { ground },
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ));
+    }
+}
+
+fn update_layer_labels(
+    tuning: Res<Tuning>,
+    camera: Single<(&Camera, &GlobalTransform), With<FlyingCamera>>,
+    grounds: Query<(&GlobalTransform, &GridCell), With<Ground>>,
+    mut labels: Query<(&LayerLabel, &mut Text, &mut Node)>,
+) {
+    let (camera, camera_transform) = camera.into_inner();
+
+    for (label, mut text, mut node) in labels.iter_mut() {
+        let Ok((transform, cell)) = grounds.get(label.ground) else {
+            continue;
+        };
+        let top = transform.translation() + Vec3::Y * CELL_TOP;
+        let Ok(position) = camera.world_to_viewport(camera_transform, top) else {
+            continue;
+        };
+
+        **text = format!("{}", cell.layer_index(tuning.cell_height));
+        node.left = Val::Px(position.x);
+        node.top = Val::Px(position.y);
+    }
+}
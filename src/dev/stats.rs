@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+
+use crate::{
+    bindings::input_unclaimed,
+    equilibrium::Equilibrium,
+    fluid_dynamics::Draining,
+    hud::{HudPanel, RightColumn},
+    map::GenerateMap,
+    shifting::{LayerChanged, Shifting},
+    water::Water,
+};
+
+pub const STATS_KEY: KeyCode = KeyCode::F3;
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SimulationStats::default())
+            .insert_resource(StatsPanel::default());
+
+        app.add_systems(Startup, setup.in_set(HudPanel::Stats))
+            .add_systems(
+                Update,
+                (
+                    toggle_stats_panel.run_if(input_unclaimed),
+                    (reset_earthworks, count_earthworks, update_stats).chain(),
+                    update_stats_display,
+                ),
+            );
+    }
+}
+
+/// Live measurements of the water and ground, for judging the simulation
+#[derive(Resource, Debug, Default)]
+pub struct SimulationStats {
+    pub total_water: f32,
+    pub wet_cells: usize,
+    pub draining: usize,
+    /// the highest and lowest water surface of the wet cells
+    pub max_surface: Option<f32>,
+    pub min_surface: Option<f32>,
+    pub shifting: usize,
+    /// ground height removed since the map was loaded
+    pub cut: f32,
+    /// ground height added since the map was loaded
+    pub fill: f32,
+    pub settled: bool,
+    pub oscillating: usize,
+}
+
+impl SimulationStats {
+    pub fn lines(&self) -> Vec<String> {
+        let height = |value: Option<f32>| match value {
+            Some(value) => format!("{:.2}", value),
+            None => "-".to_string(),
+        };
+
+        vec![
+            format!("water volume: {:.2}", self.total_water),
+            format!("wet cells: {}", self.wet_cells),
+            format!("draining: {}", self.draining),
+            format!(
+                "water surface: {} to {}",
+                height(self.min_surface),
+                height(self.max_surface)
+            ),
+            format!("shifting cells: {}", self.shifting),
+            format!("cut/fill: {:.2} / {:.2}", self.cut, self.fill),
+            format!("settled: {}", self.settled),
+            format!("oscillating cells: {}", self.oscillating),
+        ]
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct StatsPanel {
+    pub visible: bool,
+}
+
+#[derive(Component)]
+struct StatsText;
+
+fn setup(column: Single<Entity, With<RightColumn>>, mut commands: Commands) {
+    // Text to show the statistics, below the goals
+    commands
+        .entity(*column)
+        .with_child((Text::new(""), StatsText));
+}
+
+fn toggle_stats_panel(keys: Res<ButtonInput<KeyCode>>, mut panel: ResMut<StatsPanel>) {
+    if keys.just_pressed(STATS_KEY) {
+        panel.visible = !panel.visible;
+    }
+}
+
+/// A new map starts counting cut and fill from zero
+fn reset_earthworks(mut event: EventReader<GenerateMap>, mut stats: ResMut<SimulationStats>) {
+    if event.read().last().is_some() {
+        stats.cut = 0.0;
+        stats.fill = 0.0;
+    }
+}
+
+fn count_earthworks(
+    mut layer_changed: EventReader<LayerChanged>,
+    mut stats: ResMut<SimulationStats>,
+) {
+    for change in layer_changed.read() {
+        //  every cell has a unit footprint, so height is volume
+        if change.change > 0.0 {
+            stats.fill += change.change;
+        } else {
+            stats.cut -= change.change;
+        }
+    }
+}
+
+fn update_stats(
+    waters: Query<(&Water, &Transform)>,
+    draining: Query<(), With<Draining>>,
+    shifting: Query<(), With<Shifting>>,
+    equilibrium: Res<Equilibrium>,
+    mut stats: ResMut<SimulationStats>,
+) {
+    let wet: Vec<(f32, f32)> = waters
+        .iter()
+        .filter(|(water, _)| water.amount > 0.0)
+        .map(|(water, transform)| (water.amount, transform.translation.y))
+        .collect();
+
+    stats.total_water = wet.iter().map(|(amount, _)| amount).sum();
+    stats.wet_cells = wet.len();
+    stats.max_surface = wet.iter().map(|(_, surface)| *surface).reduce(f32::max);
+    stats.min_surface = wet.iter().map(|(_, surface)| *surface).reduce(f32::min);
+    stats.draining = draining.iter().count();
+    stats.shifting = shifting.iter().count();
+    stats.settled = equilibrium.settled;
+    stats.oscillating = equilibrium.oscillating.len();
+}
+
+fn update_stats_display(
+    panel: Res<StatsPanel>,
+    stats: Res<SimulationStats>,
+    mut query: Query<&mut Text, With<StatsText>>,
+) {
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+
+    **text = if panel.visible {
+        stats.lines().join("\n")
+    } else {
+        String::new()
+    };
+}
@@ -0,0 +1,452 @@
+use bevy::prelude::*;
+
+use crate::{
+    bindings::{Action, ActionInput},
+    goals::{GoalRegion, LevelGoals},
+    grid::GridCell,
+    ground::Ground,
+    map::{ClearMap, CurrentMapSettings, FlowSettings, GenerateMap, SoilSettings},
+    selection::GroundSelected,
+    soil::Soil,
+    tuning::Tuning,
+    water::{Water, WaterSink, WaterSource},
+};
+
+const TOOL_ACTIONS: [(Action, EditorTool); 6] = [
+    (Action::Slot1, EditorTool::Height),
+    (Action::Slot2, EditorTool::Soil),
+    (Action::Slot3, EditorTool::Water),
+    (Action::Slot4, EditorTool::Source),
+    (Action::Slot5, EditorTool::Sink),
+    (Action::Slot6, EditorTool::Goal),
+];
+
+pub const MAP_SIZE_MIN: i32 = 1;
+pub const MAP_SIZE_MAX: i32 = 256;
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameMode>()
+            .insert_resource(EditorState::default());
+
+        app.add_event::<ResizeMap>()
+            .add_event::<RebuildLevel>()
+            .add_event::<EditCells>();
+
+        app.add_systems(Startup, setup)
+            .add_systems(OnEnter(GameMode::Edit), request_rebuild)
+            .add_systems(OnExit(GameMode::Edit), request_rebuild)
+            .add_systems(
+                Update,
+                (
+                    toggle_game_mode,
+                    (editor_keys, apply_editor_tool, edit_cells, resize_map)
+                        .chain()
+                        .run_if(in_state(GameMode::Edit)),
+                    rebuild_level,
+                    update_editor_display,
+                ),
+            );
+    }
+}
+
+/// Whether the level is being played or edited
+#[derive(States, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameMode {
+    #[default]
+    Play,
+    Edit,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EditorTool {
+    /// set the cell's layer directly, neighbors do not follow
+    #[default]
+    Height,
+    Soil,
+    Water,
+    Source,
+    Sink,
+    Goal,
+}
+
+impl EditorTool {
+    pub const ALL: [EditorTool; 6] = [
+        EditorTool::Height,
+        EditorTool::Soil,
+        EditorTool::Water,
+        EditorTool::Source,
+        EditorTool::Sink,
+        EditorTool::Goal,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EditorTool::Height => "height",
+            EditorTool::Soil => "soil",
+            EditorTool::Water => "water",
+            EditorTool::Source => "source",
+            EditorTool::Sink => "sink",
+            EditorTool::Goal => "goal",
+        }
+    }
+}
+
+#[derive(Resource, Debug)]
+pub struct EditorState {
+    pub tool: EditorTool,
+    pub soil: Soil,
+    pub water_step: f32,
+    pub source_rate: f32,
+    pub sink_rate: f32,
+    pub goal_depth: f32,
+    pub goal_duration: Option<f32>,
+    /// the first corner of a goal region being placed
+    pub goal_start: Option<IVec2>,
+}
+
+impl Default for EditorState {
+    fn default() -> Self {
+        let cell_height = Tuning::default().cell_height;
+        Self {
+            tool: EditorTool::default(),
+            soil: Soil::Sand,
+            water_step: cell_height,
+            source_rate: 0.2,
+            sink_rate: 0.2,
+            goal_depth: cell_height,
+            goal_duration: None,
+            goal_start: None,
+        }
+    }
+}
+
+#[derive(Event, Debug)]
+pub struct ResizeMap {
+    pub size: i32,
+}
+
+/// Regenerates the map after the level definition was edited
+#[derive(Event, Debug)]
+struct RebuildLevel;
+
+/// Brings edited cells in line with the level definition, without regenerating the map
+#[derive(Event, Debug)]
+struct EditCells {
+    cells: Vec<IVec2>,
+}
+
+type EditedGrounds<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut GridCell,
+        &'static mut Transform,
+        &'static mut Soil,
+    ),
+    With<Ground>,
+>;
+type EditedWaters<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut GridCell,
+        &'static mut Transform,
+        &'static mut Water,
+    ),
+    Without<Ground>,
+>;
+
+#[derive(Component)]
+struct EditorText;
+
+fn setup(mut commands: Commands) {
+    // Text to describe the editor tools
+    commands.spawn((
+        Text::new(""),
+        EditorText,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+fn toggle_game_mode(
+    input: ActionInput,
+    mode: Res<State<GameMode>>,
+    mut next_mode: ResMut<NextState<GameMode>>,
+) {
+    if input.just_pressed(Action::ToggleEditor) {
+        next_mode.set(match mode.get() {
+            GameMode::Play => GameMode::Edit,
+            GameMode::Edit => GameMode::Play,
+        });
+    }
+}
+
+/// Switching modes rebuilds the map, so neither mode keeps the other's changes
+fn request_rebuild(mut rebuild: EventWriter<RebuildLevel>) {
+    rebuild.send(RebuildLevel);
+}
+
+fn rebuild_level(
+    mut event: EventReader<RebuildLevel>,
+    settings: Res<CurrentMapSettings>,
+    mut cleanup: EventWriter<ClearMap>,
+    mut generator: EventWriter<GenerateMap>,
+) {
+    //  many edits in one frame only need one rebuild
+    if event.read().count() == 0 {
+        return;
+    }
+
+    cleanup.send(ClearMap);
+    generator.send(GenerateMap {
+        settings: settings.value.clone(),
+    });
+}
+
+fn editor_keys(
+    input: ActionInput,
+    settings: Res<CurrentMapSettings>,
+    mut editor: ResMut<EditorState>,
+    mut resize: EventWriter<ResizeMap>,
+) {
+    for (action, tool) in TOOL_ACTIONS.iter() {
+        if input.just_pressed(*action) {
+            editor.tool = *tool;
+            editor.goal_start = None;
+        }
+    }
+
+    if input.just_pressed(Action::NextSoil) {
+        editor.soil = editor.soil.next();
+    }
+
+    if input.just_pressed(Action::GrowMap) {
+        resize.send(ResizeMap {
+            size: settings.value.size + 1,
+        });
+    }
+    if input.just_pressed(Action::ShrinkMap) {
+        resize.send(ResizeMap {
+            size: settings.value.size - 1,
+        });
+    }
+}
+
+/// Edits the level under any clicked cell, whether ground or water was picked
+fn apply_editor_tool(
+    mut ground_selected: EventReader<GroundSelected>,
+    cells: Query<&GridCell>,
+    mut editor: ResMut<EditorState>,
+    mut settings: ResMut<CurrentMapSettings>,
+    mut edit: EventWriter<EditCells>,
+) {
+    let mut changed = false;
+    let mut edited = Vec::new();
+
+    let clicked: Vec<(Entity, bool)> = ground_selected
+        .read()
+        .map(|selection| (selection.entity, selection.inverse))
+        .collect();
+
+    for (entity, inverse) in clicked {
+        let Ok(cell) = cells.get(entity) else {
+            continue;
+        };
+        let cell = cell.coordinates();
+        //  the tool's use adds, its opposite removes
+        let adding = !inverse;
+        let level = &mut settings.value;
+
+        match editor.tool {
+            EditorTool::Height => {
+                let layer = level.layer_at(cell) + if adding { 1 } else { -1 };
+                level.set_layer(cell, layer);
+            }
+            EditorTool::Soil => {
+                level.soils.retain(|soil| soil.cell != cell);
+                if adding && editor.soil != Soil::default() {
+                    level.soils.push(SoilSettings {
+                        cell,
+                        soil: editor.soil,
+                    });
+                }
+            }
+            EditorTool::Water => {
+                let step = if adding {
+                    editor.water_step
+                } else {
+                    -editor.water_step
+                };
+                let amount = level.water_at(cell) + step;
+                level.set_water(cell, amount);
+            }
+            EditorTool::Source => {
+                level.sources.retain(|source| source.cell != cell);
+                if adding {
+                    level.sources.push(FlowSettings {
+                        cell,
+                        rate: editor.source_rate,
+                    });
+                }
+            }
+            EditorTool::Sink => {
+                level.sinks.retain(|sink| sink.cell != cell);
+                if adding {
+                    level.sinks.push(FlowSettings {
+                        cell,
+                        rate: editor.sink_rate,
+                    });
+                }
+            }
+            EditorTool::Goal => {
+                if !adding {
+                    level.goals.regions.retain(|region| !region.contains(cell));
+                } else if let Some(start) = editor.goal_start.take() {
+                    level.goals.regions.push(GoalRegion {
+                        min: start.min(cell),
+                        max: start.max(cell),
+                        depth: editor.goal_depth,
+                        duration: editor.goal_duration,
+                    });
+                } else {
+                    //  wait for the second corner
+                    editor.goal_start = Some(cell);
+                    continue;
+                }
+            }
+        }
+
+        changed = true;
+        edited.push(cell);
+    }
+
+    if changed {
+        edit.send(EditCells { cells: edited });
+    }
+}
+
+/// Updates the edited cells where they are, so a click stays cheap on large maps
+fn edit_cells(
+    mut event: EventReader<EditCells>,
+    settings: Res<CurrentMapSettings>,
+    tuning: Res<Tuning>,
+    mut goals: ResMut<LevelGoals>,
+    mut grounds: EditedGrounds,
+    mut waters: EditedWaters,
+    mut commands: Commands,
+) {
+    let edited: Vec<IVec2> = event.read().flat_map(|edit| edit.cells.clone()).collect();
+    if edited.is_empty() {
+        return;
+    }
+    let level = &settings.value;
+
+    //  goals have no cells of their own, only the progress shown for them
+    *goals = LevelGoals::new(&level.goals);
+
+    for (mut cell, mut transform, mut soil) in grounds.iter_mut() {
+        let coordinates = cell.coordinates();
+        if !edited.contains(&coordinates) {
+            continue;
+        }
+        cell.layer = level.layer_at(coordinates) as f32 * tuning.cell_height;
+        transform.translation.y = cell.layer;
+        let level_soil = level.soil_at(coordinates);
+        if *soil != level_soil {
+            *soil = level_soil;
+        }
+    }
+
+    for (entity, mut cell, mut transform, mut water) in waters.iter_mut() {
+        let coordinates = cell.coordinates();
+        if !edited.contains(&coordinates) {
+            continue;
+        }
+        cell.layer = level.layer_at(coordinates) as f32 * tuning.cell_height;
+        water.amount = level.water_at(coordinates);
+        transform.translation.y = cell.layer + water.amount;
+
+        let mut flows = commands.entity(entity);
+        flows.remove::<(WaterSource, WaterSink)>();
+        if let Some(source) = level
+            .sources
+            .iter()
+            .find(|source| source.cell == coordinates)
+        {
+            flows.insert(WaterSource { rate: source.rate });
+        }
+        if let Some(sink) = level.sinks.iter().find(|sink| sink.cell == coordinates) {
+            flows.insert(WaterSink { rate: sink.rate });
+        }
+    }
+}
+
+fn resize_map(
+    mut event: EventReader<ResizeMap>,
+    mut settings: ResMut<CurrentMapSettings>,
+    mut rebuild: EventWriter<RebuildLevel>,
+) {
+    let Some(resize) = event.read().last() else {
+        return;
+    };
+
+    settings
+        .value
+        .resize(resize.size.clamp(MAP_SIZE_MIN, MAP_SIZE_MAX));
+    rebuild.send(RebuildLevel);
+}
+
+fn update_editor_display(
+    mode: Res<State<GameMode>>,
+    editor: Res<EditorState>,
+    settings: Res<CurrentMapSettings>,
+    mut query: Query<&mut Text, With<EditorText>>,
+) {
+    if !(mode.is_changed() || editor.is_changed() || settings.is_changed()) {
+        return;
+    }
+
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+
+    if *mode.get() != GameMode::Edit {
+        **text = String::new();
+        return;
+    }
+
+    let tools: Vec<String> = EditorTool::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, tool)| {
+            let marker = if *tool == editor.tool { "*" } else { "" };
+            format!("{}{}.{}", marker, index + 1, tool.name())
+        })
+        .collect();
+
+    let mut lines = vec![
+        format!(
+            "Editing {0}x{0} map (E to play-test, -/= to resize)",
+            settings.value.size
+        ),
+        format!("Tools: {}", tools.join(" ")),
+        format!("Soil (Q): {:?}", editor.soil),
+    ];
+    if let Some(start) = editor.goal_start {
+        lines.push(format!(
+            "Goal corner at {}, {}: click the opposite corner",
+            start.x, start.y
+        ));
+    }
+
+    **text = lines.join("\n");
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    editor::GameMode, grid::GridCell, map::GenerateMap, shifting::Shifting, tuning::Tuning,
+    water::Water,
+};
+
+pub struct EquilibriumPlugin;
+
+impl Plugin for EquilibriumPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Equilibrium::default());
+
+        app.add_event::<SimulationSettled>()
+            .add_event::<SimulationUnsettled>()
+            .add_event::<OscillationDetected>();
+
+        app.add_systems(
+            Update,
+            (
+                reset_equilibrium,
+                (detect_equilibrium, report_oscillation).run_if(in_state(GameMode::Play)),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Whether the map has stopped changing
+#[derive(Resource, Debug, Default)]
+pub struct Equilibrium {
+    pub settled: bool,
+    /// ticks in a row with no shifting and only small water fluxes
+    pub calm_ticks: u32,
+    /// the largest change of any cell's water in the last tick
+    pub max_flux: f32,
+    /// cells whose water keeps reversing direction without settling
+    pub oscillating: Vec<IVec2>,
+}
+
+/// Sent once the map has been calm for `Tuning::settle_ticks`
+#[derive(Event, Debug)]
+pub struct SimulationSettled;
+
+/// Sent when a settled map starts changing again
+#[derive(Event, Debug)]
+pub struct SimulationUnsettled;
+
+/// Sent when water starts sloshing back and forth between cells
+#[derive(Event, Debug)]
+pub struct OscillationDetected {
+    pub cells: Vec<IVec2>,
+}
+
+/// The recent water flow of one cell
+#[derive(Debug)]
+struct FluxHistory {
+    previous: f32,
+    /// the sign of the last flux above the threshold
+    direction: f32,
+    /// direction changes since the cell last rested
+    reversals: u32,
+}
+
+/// A new map has not settled yet
+fn reset_equilibrium(mut event: EventReader<GenerateMap>, mut equilibrium: ResMut<Equilibrium>) {
+    if event.read().last().is_some() {
+        *equilibrium = Equilibrium::default();
+    }
+}
+
+fn detect_equilibrium(
+    tuning: Res<Tuning>,
+    waters: Query<(Entity, &Water, &GridCell)>,
+    shifting: Query<(), With<Shifting>>,
+    mut histories: Local<HashMap<Entity, FluxHistory>>,
+    mut equilibrium: ResMut<Equilibrium>,
+    mut settled: EventWriter<SimulationSettled>,
+    mut unsettled: EventWriter<SimulationUnsettled>,
+) {
+    //  forget cells of cleared maps
+    histories.retain(|entity, _| waters.contains(*entity));
+
+    let mut max_flux: f32 = 0.0;
+    let mut oscillating = Vec::new();
+    for (entity, water, cell) in waters.iter() {
+        let history = histories.entry(entity).or_insert(FluxHistory {
+            previous: water.amount,
+            direction: 0.0,
+            reversals: 0,
+        });
+        let flux = water.amount - history.previous;
+        history.previous = water.amount;
+        max_flux = max_flux.max(flux.abs());
+
+        if flux.abs() < tuning.settle_threshold {
+            history.reversals = 0;
+        } else {
+            if flux.signum() == -history.direction {
+                history.reversals += 1;
+            }
+            history.direction = flux.signum();
+        }
+
+        if history.reversals >= tuning.oscillation_reversals {
+            oscillating.push(cell.coordinates());
+        }
+    }
+
+    let calm = shifting.is_empty() && max_flux < tuning.settle_threshold;
+    equilibrium.calm_ticks = if calm { equilibrium.calm_ticks + 1 } else { 0 };
+    equilibrium.max_flux = max_flux;
+
+    if !equilibrium.settled && equilibrium.calm_ticks >= tuning.settle_ticks {
+        equilibrium.settled = true;
+        settled.send(SimulationSettled);
+    } else if equilibrium.settled && !calm {
+        equilibrium.settled = false;
+        unsettled.send(SimulationUnsettled);
+    }
+
+    oscillating.sort_by_key(|cell| (cell.x, cell.y));
+    equilibrium.oscillating = oscillating;
+}
+
+/// Only reports when the set of sloshing cells grows, not every tick
+fn report_oscillation(
+    tuning: Res<Tuning>,
+    equilibrium: Res<Equilibrium>,
+    mut reported: Local<Vec<IVec2>>,
+    mut oscillation: EventWriter<OscillationDetected>,
+) {
+    if equilibrium
+        .oscillating
+        .iter()
+        .any(|cell| !reported.contains(cell))
+    {
+        warn!(
+            "water is oscillating in {} cells (level cutoff {})",
+            equilibrium.oscillating.len(),
+            tuning.level_cutoff
+        );
+        oscillation.send(OscillationDetected {
+            cells: equilibrium.oscillating.clone(),
+        });
+    }
+    *reported = equilibrium.oscillating.clone();
+}
@@ -1,9 +1,8 @@
 use bevy::prelude::*;
 
-use crate::{ground::Ground, neighborhood::Neighborhood, water::Water};
-
-const WATER_SPEED: f32 = 0.02;
-const LEVEL_CUTOFF: f32 = 0.05;
+use crate::{
+    editor::GameMode, ground::Ground, neighborhood::Neighborhood, tuning::Tuning, water::Water,
+};
 
 pub struct FluidDynamicsPlugin;
 
@@ -18,7 +17,8 @@ impl Plugin for FluidDynamicsPlugin {
                 add_draining,
                 set_drain_rate,
                 drain_water,
-            ),
+            )
+                .run_if(in_state(GameMode::Play)),
         );
     }
 }
@@ -69,6 +69,7 @@ fn add_draining(mut add_draining: EventReader<AddDrainingToEmptyWater>, mut comm
 }
 
 fn set_drain_rate(
+    tuning: Res<Tuning>,
     mut waters: Query<(Entity, &GlobalTransform, &mut Draining)>,
     neighborhoods: Query<&Neighborhood, (With<Water>, Without<Ground>)>,
     neighbors: Query<(&GlobalTransform, &Water), (With<Water>, Without<Ground>)>,
@@ -90,7 +91,7 @@ fn set_drain_rate(
 
             let difference = neighbor_transform.translation().y - water_level;
             //  no need to change when differences are so low
-            if difference.abs() < LEVEL_CUTOFF {
+            if difference.abs() < tuning.level_cutoff {
                 continue;
             }
 
@@ -114,9 +115,9 @@ fn set_drain_rate(
     }
 }
 
-fn drain_water(mut waters: Query<(&mut Water, &mut Transform, &Draining)>) {
+fn drain_water(tuning: Res<Tuning>, mut waters: Query<(&mut Water, &mut Transform, &Draining)>) {
     for (mut water, mut transform, draining) in waters.iter_mut() {
-        let drain_amount = draining.rate * WATER_SPEED;
+        let drain_amount = draining.rate * tuning.water_speed;
         water.amount += drain_amount;
         transform.translation.y += drain_amount;
     }
@@ -4,13 +4,15 @@ use bevy::{
     render::camera::ScalingMode,
 };
 
-const CAMERA_DISTANCE: f32 = 24.0;
+use crate::{
+    bindings::{input_unclaimed, Action, ActionInput},
+    cursor::GridCursor,
+    map::ResetLevel,
+    tuning::CameraTuning,
+};
 
-const CAMERA_ZOOM_RATE: f32 = 6.0;
-const CAMERA_ROTATION_SENSITIVITY: f32 = 0.06;
-const CAMERA_ZOOM_MIN: f32 = 0.5;
-const CAMERA_ZOOM_MAX: f32 = 2.0;
-const CAMERA_TRANSLATE_RATE: f32 = 12.0;
+/// Held rotate actions turn the camera as the mouse moving this many pixels a second would
+const HELD_ROTATION: f32 = 400.0;
 
 pub struct FlyingCameraPlugin;
 
@@ -18,14 +20,24 @@ impl Plugin for FlyingCameraPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(CameraDirection::default());
 
-        app.add_systems(Startup, setup).add_systems(
-            Update,
-            (
-                (keyboard_input, mouse_motion, scrollwheel_input),
-                move_camera,
+        app.add_systems(Startup, setup)
+            .add_systems(
+                Update,
+                (
+                    (
+                        keyboard_input,
+                        mouse_motion,
+                        scrollwheel_input.run_if(input_unclaimed),
+                    ),
+                    move_camera,
+                )
+                    .chain(),
             )
-                .chain(),
-        );
+            .add_systems(Update, reset_camera)
+            .add_systems(
+                Update,
+                apply_camera_distance.run_if(resource_changed::<CameraTuning>),
+            );
     }
 }
 
@@ -42,7 +54,7 @@ pub struct CameraDirection {
     pub zoom: f32,
 }
 
-fn setup(mut commands: Commands) {
+fn setup(mut commands: Commands, tuning: Res<CameraTuning>) {
     // Camera in 3D space.
     commands
         .spawn((CameraRoot, Transform::default(), Name::new("camera root")))
@@ -53,47 +65,97 @@ fn setup(mut commands: Commands) {
                 Projection::from(OrthographicProjection {
                     // 6 world units per pixel of window height.
                     scaling_mode: ScalingMode::FixedVertical {
-                        viewport_height: CAMERA_DISTANCE,
+                        viewport_height: tuning.distance,
                     },
                     ..OrthographicProjection::default_3d()
                 }),
-                Transform::from_xyz(0.0, CAMERA_DISTANCE, CAMERA_DISTANCE)
+                Transform::from_xyz(0.0, tuning.distance, tuning.distance)
                     .looking_at(Vec3::ZERO, Vec3::Y),
             ));
         });
 }
 
-fn keyboard_input(keys: Res<ButtonInput<KeyCode>>, mut direction: ResMut<CameraDirection>) {
+fn reset_camera(
+    mut event: EventReader<ResetLevel>,
+    root: Single<&mut Transform, (With<CameraRoot>, Without<FlyingCamera>)>,
+    camera: Single<&mut Projection, (With<FlyingCamera>, Without<CameraRoot>)>,
+) {
+    if event.read().last().is_none() {
+        return;
+    }
+
+    //  return to the values used at setup
+    *root.into_inner() = Transform::default();
+    if let Projection::Orthographic(ref mut orthographic) = *camera.into_inner() {
+        orthographic.scale = 1.0;
+    }
+}
+
+/// Moves the camera when its distance is tuned from the console
+fn apply_camera_distance(
+    tuning: Res<CameraTuning>,
+    camera: Single<(&mut Transform, &mut Projection), With<FlyingCamera>>,
+) {
+    let (mut transform, mut projection) = camera.into_inner();
+    *transform =
+        Transform::from_xyz(0.0, tuning.distance, tuning.distance).looking_at(Vec3::ZERO, Vec3::Y);
+    if let Projection::Orthographic(ref mut orthographic) = *projection {
+        orthographic.scaling_mode = ScalingMode::FixedVertical {
+            viewport_height: tuning.distance,
+        };
+    }
+}
+
+/// Pans with the pan actions, WASD, the arrow keys and the left stick unless rebound,
+/// except for the presses that move the grid cursor while it is on the map
+fn keyboard_input(
+    input: ActionInput,
+    cursor: Res<GridCursor>,
+    mut direction: ResMut<CameraDirection>,
+) {
     let mut dir: Vec2 = Vec2::ZERO;
+    let pans = |pan: Action, step: Action| {
+        input.pressed(pan) && !(cursor.0.is_some() && input.pressed(step))
+    };
 
-    if keys.any_pressed(vec![KeyCode::KeyD, KeyCode::ArrowRight].into_iter()) {
+    if pans(Action::PanRight, Action::CursorRight) {
         dir.x += 1.0;
     }
-    if keys.any_pressed(vec![KeyCode::KeyA, KeyCode::ArrowLeft].into_iter()) {
+    if pans(Action::PanLeft, Action::CursorLeft) {
         dir.x -= 1.0;
     }
-    if keys.any_pressed(vec![KeyCode::KeyW, KeyCode::ArrowUp].into_iter()) {
+    if pans(Action::PanForward, Action::CursorUp) {
         dir.y += 1.0;
     }
-    if keys.any_pressed(vec![KeyCode::KeyS, KeyCode::ArrowDown].into_iter()) {
+    if pans(Action::PanBack, Action::CursorDown) {
         dir.y -= 1.0;
     }
 
     direction.translation = dir;
 }
 
+/// Rotates while the rotate action is held and the mouse moves, or while a rotate direction is held
 fn mouse_motion(
-    buttons: Res<ButtonInput<MouseButton>>,
+    input: ActionInput,
+    time: Res<Time>,
     mut evr_motion: EventReader<MouseMotion>,
     mut direction: ResMut<CameraDirection>,
 ) {
     direction.rotation = Vec2::ZERO;
 
-    if buttons.pressed(MouseButton::Middle) {
+    if input.pressed(Action::Rotate) {
         for ev in evr_motion.read() {
             direction.rotation = ev.delta;
         }
     }
+
+    let held = HELD_ROTATION * time.delta_secs();
+    if input.pressed(Action::RotateLeft) {
+        direction.rotation.x -= held;
+    }
+    if input.pressed(Action::RotateRight) {
+        direction.rotation.x += held;
+    }
 }
 
 fn scrollwheel_input(
@@ -109,6 +171,7 @@ fn move_camera(
     root: Single<(&mut Transform, &GlobalTransform), (With<CameraRoot>, Without<FlyingCamera>)>,
     camera: Single<&mut Projection, (With<FlyingCamera>, Without<CameraRoot>)>,
     direction: Res<CameraDirection>,
+    tuning: Res<CameraTuning>,
     time: Res<Time>,
 ) {
     //  get values
@@ -117,20 +180,20 @@ fn move_camera(
     let delta_time: f32 = time.delta_secs();
 
     //  set the zoom
-    let delta_scale = 1. + direction.zoom * CAMERA_ZOOM_RATE * delta_time;
+    let delta_scale = 1. + direction.zoom * tuning.zoom_rate * delta_time;
     match *camera_projection {
         Projection::Orthographic(ref mut orthographic) => {
             orthographic.scale =
-                (orthographic.scale * delta_scale).clamp(CAMERA_ZOOM_MIN, CAMERA_ZOOM_MAX)
+                (orthographic.scale * delta_scale).clamp(tuning.zoom_min, tuning.zoom_max)
         }
         _ => (),
     };
 
     //  rotate with the mouse
-    root_transform.rotate_y(direction.rotation.x * CAMERA_ROTATION_SENSITIVITY);
+    root_transform.rotate_y(direction.rotation.x * tuning.rotation_sensitivity);
 
     //  we must move the camera root relative to the facing direction of the camera
     let relative_direction = global_root_transform.rotation()
         * Vec3::new(direction.translation.x, 0.0, -direction.translation.y);
-    root_transform.translation += relative_direction * CAMERA_TRANSLATE_RATE * delta_time;
+    root_transform.translation += relative_direction * tuning.translate_rate * delta_time;
 }
@@ -0,0 +1,229 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    editor::GameMode,
+    grid::GridCell,
+    hud::{HudPanel, RightColumn},
+    map::GenerateMap,
+    water::Water,
+};
+
+pub struct GoalsPlugin;
+
+impl Plugin for GoalsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LevelGoals::default());
+
+        app.add_event::<LevelComplete>().add_event::<LevelFailed>();
+
+        app.add_systems(Startup, setup.in_set(HudPanel::Goals))
+            .add_systems(
+                Update,
+                (
+                    load_goals,
+                    track_goals.run_if(in_state(GameMode::Play)),
+                    (update_goal_display, log_level_result),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GoalSettings {
+    #[serde(default)]
+    pub regions: Vec<GoalRegion>,
+    /// seconds before the level is failed, if any
+    #[serde(default)]
+    pub time_limit: Option<f32>,
+}
+
+/// A rectangle of cells (inclusive) that must hold water
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalRegion {
+    pub min: IVec2,
+    pub max: IVec2,
+    /// the water amount every cell must reach
+    pub depth: f32,
+    /// seconds the water must be held, if any
+    #[serde(default)]
+    pub duration: Option<f32>,
+}
+
+impl GoalRegion {
+    pub fn contains(&self, cell: IVec2) -> bool {
+        (self.min.x..=self.max.x).contains(&cell.x) && (self.min.y..=self.max.y).contains(&cell.y)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GoalProgress {
+    pub region: GoalRegion,
+    pub filled_cells: usize,
+    pub total_cells: usize,
+    pub held: f32,
+    pub met: bool,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct LevelGoals {
+    pub progress: Vec<GoalProgress>,
+    pub time_limit: Option<f32>,
+    pub elapsed: f32,
+    pub finished: bool,
+}
+
+impl LevelGoals {
+    /// Fresh progress towards a level's goals
+    pub fn new(settings: &GoalSettings) -> Self {
+        LevelGoals {
+            progress: settings
+                .regions
+                .iter()
+                .map(|region| GoalProgress {
+                    region: region.clone(),
+                    filled_cells: 0,
+                    total_cells: 0,
+                    held: 0.0,
+                    met: false,
+                })
+                .collect(),
+            time_limit: settings.time_limit,
+            ..default()
+        }
+    }
+}
+
+#[derive(Event, Debug)]
+pub struct LevelComplete {
+    pub elapsed: f32,
+}
+
+#[derive(Event, Debug)]
+pub struct LevelFailed {
+    pub elapsed: f32,
+}
+
+#[derive(Component)]
+struct GoalText;
+
+fn setup(column: Single<Entity, With<RightColumn>>, mut commands: Commands) {
+    // Text to describe the level goals
+    commands
+        .entity(*column)
+        .with_child((Text::new(""), GoalText));
+}
+
+fn load_goals(mut event: EventReader<GenerateMap>, mut goals: ResMut<LevelGoals>) {
+    for generation in event.read() {
+        *goals = LevelGoals::new(&generation.settings.goals);
+    }
+}
+
+fn track_goals(
+    time: Res<Time>,
+    mut goals: ResMut<LevelGoals>,
+    waters: Query<(&GridCell, &Water)>,
+    mut complete: EventWriter<LevelComplete>,
+    mut failed: EventWriter<LevelFailed>,
+) {
+    if goals.finished || goals.progress.is_empty() {
+        return;
+    }
+
+    let delta = time.delta_secs();
+    goals.elapsed += delta;
+
+    for goal in goals.progress.iter_mut() {
+        goal.filled_cells = 0;
+        goal.total_cells = 0;
+
+        for (cell, water) in waters.iter() {
+            if goal.region.contains(cell.coordinates()) {
+                goal.total_cells += 1;
+                if water.amount >= goal.region.depth {
+                    goal.filled_cells += 1;
+                }
+            }
+        }
+
+        //  the timer restarts whenever the region is not completely filled
+        if goal.total_cells > 0 && goal.filled_cells == goal.total_cells {
+            goal.held += delta;
+        } else {
+            goal.held = 0.0;
+        }
+
+        goal.met = goal.total_cells > 0 && goal.held >= goal.region.duration.unwrap_or(0.0);
+    }
+
+    if goals.progress.iter().all(|goal| goal.met) {
+        goals.finished = true;
+        complete.send(LevelComplete {
+            elapsed: goals.elapsed,
+        });
+    } else if goals.time_limit.is_some_and(|limit| goals.elapsed >= limit) {
+        goals.finished = true;
+        failed.send(LevelFailed {
+            elapsed: goals.elapsed,
+        });
+    }
+}
+
+fn log_level_result(
+    mut complete: EventReader<LevelComplete>,
+    mut failed: EventReader<LevelFailed>,
+) {
+    for result in complete.read() {
+        info!("level complete in {:.1}s", result.elapsed);
+    }
+    for result in failed.read() {
+        info!("level failed after {:.1}s", result.elapsed);
+    }
+}
+
+fn update_goal_display(goals: Res<LevelGoals>, mut query: Query<&mut Text, With<GoalText>>) {
+    if !goals.is_changed() {
+        return;
+    }
+
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+
+    let mut lines: Vec<String> = goals
+        .progress
+        .iter()
+        .enumerate()
+        .map(|(index, goal)| {
+            let mut line = format!(
+                "Goal {}: {}/{} cells",
+                index + 1,
+                goal.filled_cells,
+                goal.total_cells
+            );
+            if let Some(duration) = goal.region.duration {
+                line += &format!(" ({:.1}/{:.1}s)", goal.held.min(duration), duration);
+            }
+            line
+        })
+        .collect();
+
+    if let Some(limit) = goals.time_limit {
+        lines.push(format!(
+            "Time left: {:.0}s",
+            (limit - goals.elapsed).max(0.0)
+        ));
+    }
+
+    if goals.finished {
+        lines.push(if goals.progress.iter().all(|goal| goal.met) {
+            String::from("Level complete!")
+        } else {
+            String::from("Level failed.")
+        });
+    }
+
+    **text = lines.join("\n");
+}
@@ -2,8 +2,6 @@ use bevy::prelude::*;
 
 use crate::neighborhood::Neighborhood;
 
-pub const CELL_HEIGHT: f32 = 0.5;
-
 #[derive(Component, Debug, Default)]
 pub struct GridCell {
     pub row: i32,
@@ -11,6 +9,17 @@ pub struct GridCell {
     pub layer: f32,
 }
 
+impl GridCell {
+    pub fn coordinates(&self) -> IVec2 {
+        IVec2::new(self.row, self.col)
+    }
+
+    /// The layer the cell is at, counted in whole cell heights
+    pub fn layer_index(&self, cell_height: f32) -> i32 {
+        (self.layer / cell_height).round() as i32
+    }
+}
+
 impl PartialEq for GridCell {
     fn eq(&self, rhs: &GridCell) -> bool {
         self.row == rhs.row && self.col == rhs.col
@@ -18,24 +27,24 @@ impl PartialEq for GridCell {
 }
 
 trait GridBuilder {
-    fn from_grid_coordinates(coordinations: IVec3, offset: f32) -> Self;
+    fn from_grid_coordinates(coordinations: IVec3, offset: f32, cell_height: f32) -> Self;
 }
 
 impl GridBuilder for GridCell {
-    fn from_grid_coordinates(coordinates: IVec3, _offset: f32) -> Self {
+    fn from_grid_coordinates(coordinates: IVec3, _offset: f32, cell_height: f32) -> Self {
         Self {
             row: coordinates.x,
             col: coordinates.y,
-            layer: coordinates.z as f32 * CELL_HEIGHT,
+            layer: coordinates.z as f32 * cell_height,
         }
     }
 }
 
 impl GridBuilder for Transform {
-    fn from_grid_coordinates(coordinates: IVec3, offset: f32) -> Self {
+    fn from_grid_coordinates(coordinates: IVec3, offset: f32, cell_height: f32) -> Self {
         Transform::from_xyz(
             coordinates.x as f32 - offset,
-            coordinates.z as f32 * CELL_HEIGHT,
+            coordinates.z as f32 * cell_height,
             coordinates.y as f32 - offset,
         )
     }
@@ -49,11 +58,17 @@ pub struct GridCellBundle {
 }
 
 impl GridCellBundle {
-    pub fn new(grid_offset: f32, grid_coordinates: IVec3) -> Self {
+    pub fn new(grid_offset: f32, grid_coordinates: IVec3, cell_height: f32) -> Self {
         Self {
-            transform: Transform::from_grid_coordinates(grid_coordinates, grid_offset),
-            cell: GridCell::from_grid_coordinates(grid_coordinates, grid_offset),
+            transform: Transform::from_grid_coordinates(grid_coordinates, grid_offset, cell_height),
+            cell: GridCell::from_grid_coordinates(grid_coordinates, grid_offset, cell_height),
             neighborhood: Neighborhood::default(),
         }
     }
+
+    /// Raises the transform above the cell's layer, e.g. for water resting on the ground
+    pub fn with_height_offset(mut self, offset: f32) -> Self {
+        self.transform.translation.y += offset;
+        self
+    }
 }
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_sets(
+            Startup,
+            (HudPanel::Goals, HudPanel::Stats, HudPanel::Bindings).chain(),
+        );
+
+        app.add_systems(PreStartup, setup);
+    }
+}
+
+/// The column down the right of the screen, where panels stack instead of overlapping
+#[derive(Component)]
+pub struct RightColumn;
+
+/// The panels of the right column, spawned from top to bottom in this order
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HudPanel {
+    Goals,
+    Stats,
+    Bindings,
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        RightColumn,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            right: Val::Px(12.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::FlexEnd,
+            row_gap: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
@@ -1,33 +1,72 @@
+mod bindings;
+mod campaign;
+mod cursor;
 mod dev;
+mod editor;
 mod environment;
+mod equilibrium;
 mod fluid_dynamics;
 mod flying_camera;
+mod goals;
 mod grid;
 mod ground;
+mod hud;
 mod map;
 mod mesh;
 mod neighborhood;
 mod pair;
+mod scoring;
 mod selection;
 mod shifting;
+mod soil;
+mod storage;
+mod tools;
+mod tuning;
 mod water;
 
+use std::path::PathBuf;
+
 use bevy::prelude::*;
+use bindings::BindingsPlugin;
+use campaign::CampaignPlugin;
+use clap::Parser;
+use cursor::CursorPlugin;
 use dev::DevPlugin;
+use editor::EditorPlugin;
 use environment::EnvironmentPlugin;
+use equilibrium::EquilibriumPlugin;
 use fluid_dynamics::FluidDynamicsPlugin;
 use flying_camera::FlyingCameraPlugin;
+use goals::GoalsPlugin;
+use hud::HudPlugin;
 use map::MapPlugin;
+use scoring::ScoringPlugin;
 use selection::SelectionPlugin;
 use shifting::ShiftPlugin;
+use storage::StoragePlugin;
+use tools::ToolsPlugin;
+use tuning::TuningPlugin;
 use water::WaterPlugin;
 
+/// Options given on the command line
+#[derive(Parser, Resource, Debug, Default)]
+#[command(version, about)]
+pub struct LaunchOptions {
+    /// console script to run once the game has started
+    #[arg(long)]
+    pub script: Option<PathBuf>,
+    /// tuning file to use instead of assets/tuning.json
+    #[arg(long)]
+    pub tuning: Option<PathBuf>,
+}
+
 pub struct AppPlugin;
 
 impl Plugin for AppPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(DefaultPlugins).add_plugins((
             DevPlugin,
+            EditorPlugin,
             EnvironmentPlugin,
             SelectionPlugin,
             MapPlugin,
@@ -35,6 +74,13 @@ impl Plugin for AppPlugin {
             ShiftPlugin,
             WaterPlugin,
             FluidDynamicsPlugin,
-        ));
+            GoalsPlugin,
+            ScoringPlugin,
+            CampaignPlugin,
+            StoragePlugin,
+            TuningPlugin,
+            EquilibriumPlugin,
+        ))
+        .add_plugins((ToolsPlugin, CursorPlugin, BindingsPlugin, HudPlugin));
     }
 }
@@ -1,6 +1,10 @@
 use bevy::prelude::*;
-use hill_builder::AppPlugin;
+use clap::Parser;
+use hill_builder::{AppPlugin, LaunchOptions};
 
 fn main() {
-    App::new().add_plugins(AppPlugin).run();
+    App::new()
+        .insert_resource(LaunchOptions::parse())
+        .add_plugins(AppPlugin)
+        .run();
 }
@@ -1,41 +1,55 @@
-use bevy::prelude::*;
+use std::collections::HashMap;
+
+use bevy::{asset::AssetLoadFailedEvent, prelude::*};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    dev::user_testing::update_water_selection,
+    bindings::{Action, ActionInput},
+    goals::GoalSettings,
     grid::{GridCell, GridCellBundle},
     ground::Ground,
     mesh::{create_cube_mesh, CubeBundle},
     neighborhood::Neighborhood,
     pair::Pair,
-    selection::{update_ground_selection, update_material_on},
-    water::{Water, WATER_COLOR, WATER_MESH_SCALE},
+    scoring::BudgetSettings,
+    selection::{
+        restore_soil_material, update_ground_hover, update_ground_selection, update_material_on,
+        update_water_hover, update_water_selection, HoverMaterial,
+    },
+    soil::{Soil, SoilMaterials},
+    storage::{map_asset_path, MapAsset, MAP_FORMAT_VERSION},
+    tuning::{apply_map_tuning, Tuning, TuningOverrides},
+    water::{Water, WaterSink, WaterSource, WATER_COLOR},
 };
 
 const MAP_SIZE_DEFAULT: i32 = 8;
 
-const GROUND_COLOR: Color = Color::srgb(0.0, 0.9, 0.1);
-
 pub struct MapPlugin;
 
 impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(CurrentMapSettings::default());
+        app.insert_resource(CurrentMapSettings::default())
+            .insert_resource(WatchedMap::default());
 
         app.add_event::<GenerateMap>()
             .add_event::<ClearMap>()
+            .add_event::<ResetLevel>()
+            .add_event::<LoadMap>()
             .add_event::<ConnectGridCells>();
 
         app.add_systems(
             Update,
             (
-                clear_map,
                 store_map,
                 (
+                    (reset_key, reset_level, load_map, generate_loaded_map),
+                    clear_map,
+                    apply_map_tuning,
                     generate_map,
                     (connect_grid_cells::<Ground>, connect_grid_cells::<Water>),
                 )
                     .chain(),
+                update_soil_material.run_if(resource_exists::<SoilMaterials>),
             ),
         );
     }
@@ -54,26 +68,96 @@ pub struct GenerateMap {
 #[derive(Event)]
 pub struct ClearMap;
 
+/// Regenerates the current level from its original definition
+#[derive(Event)]
+pub struct ResetLevel;
+
+/// The asset of the last loaded map file, so edits to it regenerate the level
+#[derive(Resource, Debug, Default)]
+pub struct WatchedMap {
+    pub handle: Option<Handle<MapAsset>>,
+    /// the level is generated once the asset finishes loading
+    pub pending: bool,
+}
+
+/// Replaces the current map with one read from the maps folder
+#[derive(Event)]
+pub struct LoadMap {
+    pub name: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MapGenerationSettings {
+    /// the format version the map was written with, see `storage::MAP_FORMAT_VERSION`
+    #[serde(default)]
+    pub version: u64,
+    #[serde(default)]
+    pub metadata: MapMetadata,
     pub size: i32,
     pub terrain: TerrainSettings,
+    #[serde(default)]
+    pub water: Vec<WaterSettings>,
+    #[serde(default)]
+    pub sources: Vec<FlowSettings>,
+    #[serde(default)]
+    pub sinks: Vec<FlowSettings>,
+    #[serde(default)]
+    pub soils: Vec<SoilSettings>,
+    #[serde(default)]
+    pub goals: GoalSettings,
+    #[serde(default)]
+    pub budget: BudgetSettings,
+    #[serde(default, skip_serializing_if = "TuningOverrides::is_empty")]
+    pub tuning: TuningOverrides,
 }
 
 impl Default for MapGenerationSettings {
     fn default() -> Self {
         Self {
+            version: MAP_FORMAT_VERSION,
+            metadata: Default::default(),
             size: MAP_SIZE_DEFAULT,
             terrain: Default::default(),
+            water: Vec::new(),
+            sources: Vec::new(),
+            sinks: Vec::new(),
+            soils: Vec::new(),
+            goals: Default::default(),
+            budget: Default::default(),
+            tuning: Default::default(),
         }
     }
 }
 
+/// Describes a map when it is listed, none of it affects generation
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct MapMetadata {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub enum TerrainSettings {
     #[default]
     FLAT,
     CURVED(CurvedTerrainSettings),
+    CUSTOM(CustomTerrainSettings),
+}
+
+impl TerrainSettings {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TerrainSettings::FLAT => "flat",
+            TerrainSettings::CURVED(_) => "curved",
+            TerrainSettings::CUSTOM(_) => "custom",
+        }
+    }
 }
 
 #[derive(Resource, Default, Debug, Clone, Serialize, Deserialize)]
@@ -84,19 +168,225 @@ pub struct CurvedTerrainSettings {
     pub phase_shift: Vec2,
 }
 
+/// Explicit layers for every cell, stored row by row
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct CustomTerrainSettings {
+    pub layers: Vec<i32>,
+}
+
+/// Water placed on a cell when the map is generated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaterSettings {
+    pub cell: IVec2,
+    pub amount: f32,
+}
+
+/// A cell that keeps producing (source) or removing (sink) water at a rate (per second)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlowSettings {
+    pub cell: IVec2,
+    pub rate: f32,
+}
+
+/// A cell made of something other than the default soil
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoilSettings {
+    pub cell: IVec2,
+    pub soil: Soil,
+}
+
+impl MapGenerationSettings {
+    pub fn contains(&self, cell: IVec2) -> bool {
+        (0..self.size).contains(&cell.x) && (0..self.size).contains(&cell.y)
+    }
+
+    pub fn layer_at(&self, cell: IVec2) -> i32 {
+        match &self.terrain {
+            TerrainSettings::FLAT => 0,
+            TerrainSettings::CURVED(settings) => generate_layer(cell.x, cell.y, settings),
+            TerrainSettings::CUSTOM(settings) => settings
+                .layers
+                .get((cell.x * self.size + cell.y) as usize)
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn water_at(&self, cell: IVec2) -> f32 {
+        self.water
+            .iter()
+            .filter(|water| water.cell == cell)
+            .map(|water| water.amount)
+            .sum()
+    }
+
+    pub fn soil_at(&self, cell: IVec2) -> Soil {
+        self.soils
+            .iter()
+            .rev()
+            .find(|soil| soil.cell == cell)
+            .map(|soil| soil.soil)
+            .unwrap_or_default()
+    }
+
+    /// Replaces the terrain with explicit layers, so single cells can be edited
+    pub fn make_custom(&mut self) {
+        if matches!(self.terrain, TerrainSettings::CUSTOM(_)) {
+            return;
+        }
+
+        let mut layers = Vec::with_capacity((self.size * self.size) as usize);
+        for i in 0..self.size {
+            for j in 0..self.size {
+                layers.push(self.layer_at(IVec2::new(i, j)));
+            }
+        }
+        self.terrain = TerrainSettings::CUSTOM(CustomTerrainSettings { layers });
+    }
+
+    pub fn set_layer(&mut self, cell: IVec2, layer: i32) {
+        self.make_custom();
+
+        let index = (cell.x * self.size + cell.y) as usize;
+        if let TerrainSettings::CUSTOM(settings) = &mut self.terrain {
+            if let Some(value) = settings.layers.get_mut(index) {
+                *value = layer;
+            }
+        }
+    }
+
+    pub fn set_water(&mut self, cell: IVec2, amount: f32) {
+        self.water.retain(|water| water.cell != cell);
+        if amount > 0.0 {
+            self.water.push(WaterSettings { cell, amount });
+        }
+    }
+
+    /// Changes the map size, keeping every cell that still fits
+    pub fn resize(&mut self, size: i32) {
+        self.make_custom();
+
+        let mut layers = Vec::with_capacity((size * size) as usize);
+        for i in 0..size {
+            for j in 0..size {
+                let cell = IVec2::new(i, j);
+                layers.push(if self.contains(cell) {
+                    self.layer_at(cell)
+                } else {
+                    0
+                });
+            }
+        }
+        self.size = size;
+        self.terrain = TerrainSettings::CUSTOM(CustomTerrainSettings { layers });
+
+        let max = IVec2::splat(size - 1);
+        self.water.retain(|water| water.cell.cmple(max).all());
+        self.sources.retain(|source| source.cell.cmple(max).all());
+        self.sinks.retain(|sink| sink.cell.cmple(max).all());
+        self.soils.retain(|soil| soil.cell.cmple(max).all());
+        self.goals
+            .regions
+            .retain(|region| region.min.cmple(max).all());
+        for region in self.goals.regions.iter_mut() {
+            region.max = region.max.min(max);
+        }
+    }
+}
+
 fn clear_map(
     mut event: EventReader<ClearMap>,
-    mut cells: Query<Entity, With<GridCell>>,
+    cells: Query<Entity, With<GridCell>>,
+    pairs: Query<Entity, With<Pair>>,
     mut commands: Commands,
 ) {
-    //  TODO: despawn water
     for _ in event.read() {
-        for entity in cells.iter_mut() {
+        //  grounds and waters are both grid cells
+        for entity in cells.iter().chain(pairs.iter()) {
             commands.entity(entity).despawn_recursive();
         }
     }
 }
 
+fn reset_key(input: ActionInput, mut reset: EventWriter<ResetLevel>) {
+    if input.just_pressed(Action::ResetLevel) {
+        reset.send(ResetLevel);
+    }
+}
+
+fn reset_level(
+    mut event: EventReader<ResetLevel>,
+    settings: Res<CurrentMapSettings>,
+    mut cleanup: EventWriter<ClearMap>,
+    mut generator: EventWriter<GenerateMap>,
+) {
+    for _ in event.read() {
+        cleanup.send(ClearMap);
+        generator.send(GenerateMap {
+            settings: settings.value.clone(),
+        });
+    }
+}
+
+/// Starts loading the map file through the asset server
+fn load_map(
+    mut event: EventReader<LoadMap>,
+    asset_server: Res<AssetServer>,
+    maps: Res<Assets<MapAsset>>,
+    mut watched: ResMut<WatchedMap>,
+) {
+    for load in event.read() {
+        let path = map_asset_path(&load.name);
+        let handle = asset_server.load(path.clone());
+
+        //  a map loaded before is read again, the file may have been saved since
+        if maps.contains(&handle) {
+            asset_server.reload(path);
+            watched.pending = false;
+        } else {
+            watched.pending = true;
+        }
+        watched.handle = Some(handle);
+    }
+}
+
+/// Generates the level once its map file has loaded, and again whenever the file changes
+fn generate_loaded_map(
+    mut event: EventReader<AssetEvent<MapAsset>>,
+    mut failed: EventReader<AssetLoadFailedEvent<MapAsset>>,
+    mut watched: ResMut<WatchedMap>,
+    maps: Res<Assets<MapAsset>>,
+    mut cleanup: EventWriter<ClearMap>,
+    mut generator: EventWriter<GenerateMap>,
+) {
+    for failure in failed.read() {
+        error!("failed to load map {}: {}", failure.path, failure.error);
+        if watched.handle.as_ref().is_some_and(|handle| handle.id() == failure.id) {
+            watched.pending = false;
+        }
+    }
+
+    let Some(handle) = watched.handle.clone() else {
+        return;
+    };
+    let modified = event.read().any(|asset| asset.is_modified(handle.id()));
+    let Some(map) = maps.get(&handle) else {
+        return;
+    };
+    if !(modified || watched.pending) {
+        return;
+    }
+
+    if modified && !watched.pending {
+        info!("map file changed, regenerating the level");
+    }
+    watched.pending = false;
+    cleanup.send(ClearMap);
+    generator.send(GenerateMap {
+        settings: map.settings.clone(),
+    });
+}
+
 fn store_map(mut event: EventReader<GenerateMap>, mut settings: ResMut<CurrentMapSettings>) {
     for generation in event.read() {
         settings.value = generation.settings.clone();
@@ -108,51 +398,86 @@ fn generate_map(
     mut commands: Commands,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut meshes: ResMut<Assets<Mesh>>,
+    tuning: Res<Tuning>,
     mut connect_grid_cells: EventWriter<ConnectGridCells>,
 ) {
     for generation in event.read() {
         let hover_matl = materials.add(Color::WHITE);
-        let ground_matl = materials.add(GROUND_COLOR);
+        commands.insert_resource(HoverMaterial(hover_matl.clone()));
+        let soil_matls: HashMap<Soil, Handle<StandardMaterial>> = Soil::ALL
+            .iter()
+            .map(|soil| (*soil, materials.add(soil.color())))
+            .collect();
+        commands.insert_resource(SoilMaterials(soil_matls.clone()));
         let ground_mesh_handle: Handle<Mesh> = meshes.add(create_cube_mesh(None));
 
         let water_matl = materials.add(WATER_COLOR);
-        let water_mesh_handle = meshes.add(create_cube_mesh(Some(WATER_MESH_SCALE)));
+        let water_mesh_handle = meshes.add(create_cube_mesh(Some(tuning.water_mesh_scale)));
 
         let map_size = generation.settings.size;
         let map_offset: f32 = map_size as f32 / 2.0;
 
         for i in 0..map_size {
             for j in 0..map_size {
-                let layer: i32 = match &generation.settings.terrain {
-                    TerrainSettings::FLAT => 0,
-                    TerrainSettings::CURVED(settings) => generate_layer(i, j, &settings),
-                };
+                let coordinates = IVec2::new(i, j);
+                let layer: i32 = generation.settings.layer_at(coordinates);
+                let water_amount: f32 = generation.settings.water_at(coordinates);
+                let soil = generation.settings.soil_at(coordinates);
 
                 // render the mesh with the custom texture, and add the marker.
                 let ground_entity = commands
                     .spawn((
                         Ground,
-                        CubeBundle::new(ground_mesh_handle.clone(), ground_matl.clone()),
-                        GridCellBundle::new(map_offset, IVec3::new(i, j, layer)),
+                        soil,
+                        CubeBundle::new(ground_mesh_handle.clone(), soil_matls[&soil].clone()),
+                        GridCellBundle::new(
+                            map_offset,
+                            IVec3::new(i, j, layer),
+                            tuning.cell_height,
+                        ),
                     ))
                     .observe(update_material_on::<Pointer<Over>>(hover_matl.clone()))
-                    .observe(update_material_on::<Pointer<Out>>(ground_matl.clone()))
-                    .observe(update_ground_selection::<Pointer<Down>>())
+                    .observe(restore_soil_material::<Pointer<Out>>())
+                    .observe(update_ground_selection())
+                    .observe(update_ground_hover::<Pointer<Over>>(true))
+                    .observe(update_ground_hover::<Pointer<Out>>(false))
                     .id();
 
                 let water_entity = commands
                     .spawn((
                         Name::new("water"),
                         Water {
-                            amount: 0.0,
+                            amount: water_amount,
                             ..default()
                         },
-                        GridCellBundle::new(map_offset, IVec3::new(i, j, layer)),
+                        GridCellBundle::new(
+                            map_offset,
+                            IVec3::new(i, j, layer),
+                            tuning.cell_height,
+                        )
+                        .with_height_offset(water_amount),
                         CubeBundle::new(water_mesh_handle.clone(), water_matl.clone()),
                     ))
-                    .observe(update_water_selection::<Pointer<Down>>())
+                    .observe(update_water_selection())
+                    .observe(update_water_hover::<Pointer<Over>>(true))
+                    .observe(update_water_hover::<Pointer<Out>>(false))
                     .id();
 
+                for source in generation.settings.sources.iter() {
+                    if source.cell == coordinates {
+                        commands
+                            .entity(water_entity)
+                            .insert(WaterSource { rate: source.rate });
+                    }
+                }
+                for sink in generation.settings.sinks.iter() {
+                    if sink.cell == coordinates {
+                        commands
+                            .entity(water_entity)
+                            .insert(WaterSink { rate: sink.rate });
+                    }
+                }
+
                 commands.spawn((
                     Name::new("Pair"),
                     Pair {
@@ -168,6 +493,16 @@ fn generate_map(
     }
 }
 
+/// Recolors ground whose soil was changed while playing
+fn update_soil_material(
+    soil_materials: Res<SoilMaterials>,
+    mut grounds: Query<(&Soil, &mut MeshMaterial3d<StandardMaterial>), Changed<Soil>>,
+) {
+    for (soil, mut material) in grounds.iter_mut() {
+        material.0 = soil_materials.0[soil].clone();
+    }
+}
+
 fn generate_layer(x: i32, y: i32, settings: &CurvedTerrainSettings) -> i32 {
     (settings.amplitude.x * ops::sin(x as f32 * settings.wavelength.x + settings.phase_shift.x)
         + settings.vertical_shift.x
@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    goals::{LevelComplete, LevelFailed, LevelGoals},
+    map::GenerateMap,
+    shifting::LayerChanged,
+    tuning::Tuning,
+};
+
+const MAX_STARS: u32 = 3;
+const MAX_SCORE: f32 = 1000.0;
+
+pub struct ScoringPlugin;
+
+impl Plugin for ScoringPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(EditBudget::default());
+
+        app.add_event::<LevelScored>();
+
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (
+                (load_budget, count_edits).chain(),
+                score_level,
+                update_budget_display,
+            ),
+        );
+    }
+}
+
+/// Limits on the player's edits, read from the level file
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BudgetSettings {
+    /// the number of times the player may use a tool, however many cells each use covers
+    #[serde(default)]
+    pub max_edits: Option<u32>,
+    /// the number of layer changes allowed in total
+    #[serde(default)]
+    pub max_layer_changes: Option<u32>,
+    /// whether cascaded neighbor shifts count against the layer changes
+    #[serde(default)]
+    pub count_cascades: bool,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct EditBudget {
+    pub settings: BudgetSettings,
+    pub edits: u32,
+    pub layer_changes: u32,
+}
+
+impl EditBudget {
+    pub fn can_edit(&self) -> bool {
+        self.settings.max_edits.is_none_or(|max| self.edits < max)
+            && self
+                .settings
+                .max_layer_changes
+                .is_none_or(|max| self.layer_changes < max)
+    }
+
+    /// The fraction of the tightest budget that is left, 1.0 without a budget
+    pub fn remaining(&self) -> f32 {
+        let edits = self
+            .settings
+            .max_edits
+            .map(|max| remaining_fraction(self.edits, max));
+        let layer_changes = self
+            .settings
+            .max_layer_changes
+            .map(|max| remaining_fraction(self.layer_changes, max));
+
+        edits.into_iter().chain(layer_changes).fold(1.0, f32::min)
+    }
+}
+
+fn remaining_fraction(used: u32, max: u32) -> f32 {
+    if max == 0 {
+        return 0.0;
+    }
+    1.0 - (used as f32 / max as f32).min(1.0)
+}
+
+#[derive(Event, Debug)]
+pub struct LevelScored {
+    pub completed: bool,
+    pub score: u32,
+    pub stars: u32,
+}
+
+#[derive(Component)]
+struct BudgetText;
+
+fn setup(mut commands: Commands) {
+    // Text to describe the edit budget and final score
+    commands.spawn((
+        Text::new(""),
+        BudgetText,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            right: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+fn load_budget(mut event: EventReader<GenerateMap>, mut budget: ResMut<EditBudget>) {
+    for generation in event.read() {
+        *budget = EditBudget {
+            settings: generation.settings.budget.clone(),
+            ..default()
+        };
+    }
+}
+
+/// Edits are spent by the tools, once per use, the layer changes are counted here
+fn count_edits(
+    mut layer_changed: EventReader<LayerChanged>,
+    tuning: Res<Tuning>,
+    mut budget: ResMut<EditBudget>,
+) {
+    for change in layer_changed.read() {
+        if !change.cascaded || budget.settings.count_cascades {
+            budget.layer_changes += (change.change.abs() / tuning.cell_height).round() as u32;
+        }
+    }
+}
+
+fn score_level(
+    mut complete: EventReader<LevelComplete>,
+    mut failed: EventReader<LevelFailed>,
+    goals: Res<LevelGoals>,
+    budget: Res<EditBudget>,
+    mut scored: EventWriter<LevelScored>,
+) {
+    let completed = complete.read().count() > 0;
+    let ended = completed || failed.read().count() > 0;
+    if !ended {
+        return;
+    }
+
+    //  partially filled regions still count towards the score
+    let goal_fraction = if goals.progress.is_empty() {
+        1.0
+    } else {
+        goals
+            .progress
+            .iter()
+            .map(|goal| match goal.total_cells {
+                _ if goal.met => 1.0,
+                0 => 0.0,
+                total => goal.filled_cells as f32 / total as f32,
+            })
+            .sum::<f32>()
+            / goals.progress.len() as f32
+    };
+
+    let remaining = budget.remaining();
+    let score = (MAX_SCORE * goal_fraction * (0.5 + 0.5 * remaining)).round() as u32;
+    let stars = if completed {
+        1 + (remaining >= 0.25) as u32 + (remaining >= 0.5) as u32
+    } else {
+        0
+    };
+
+    scored.send(LevelScored {
+        completed,
+        score,
+        stars: stars.min(MAX_STARS),
+    });
+}
+
+fn update_budget_display(
+    budget: Res<EditBudget>,
+    mut scored: EventReader<LevelScored>,
+    mut query: Query<&mut Text, With<BudgetText>>,
+) {
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+
+    if let Some(result) = scored.read().last() {
+        **text = format!(
+            "Score: {} ({}/{} stars)",
+            result.score, result.stars, MAX_STARS
+        );
+        return;
+    }
+
+    if !budget.is_changed() {
+        return;
+    }
+
+    let mut lines = Vec::new();
+    if let Some(max) = budget.settings.max_edits {
+        lines.push(format!("Edits: {}/{}", budget.edits, max));
+    }
+    if let Some(max) = budget.settings.max_layer_changes {
+        lines.push(format!("Layer changes: {}/{}", budget.layer_changes, max));
+    }
+    **text = lines.join("\n");
+}
@@ -1,21 +1,37 @@
+use std::collections::HashSet;
+
 use bevy::prelude::*;
 
-use crate::dev::user_testing::{ManuallyIncreaseWater, WaterToggle};
+use crate::{
+    bindings::{Action, ActionInput},
+    map::GenerateMap,
+    pair::Pair,
+    soil::{Soil, SoilMaterials},
+};
 
 pub struct SelectionPlugin;
 
 impl Plugin for SelectionPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(MeshPickingPlugin)
-            .add_event::<GroundSelected>();
+            .add_event::<GroundSelected>()
+            .insert_resource(HoveredGround::default())
+            .insert_resource(SelectedCells::default())
+            .add_systems(Update, clear_selected_cells);
     }
 }
 
 #[derive(Event, Debug)]
 pub struct GroundSelected {
     pub entity: Entity,
+    /// whether the click or press asked for the tool's opposite
+    pub inverse: bool,
 }
 
+/// The ground cell under the pointer, or under the water the pointer is on
+#[derive(Resource, Debug, Default)]
+pub struct HoveredGround(pub Option<Entity>);
+
 /// An observer that updates the entity's material to the one specified.
 pub fn update_material_on<E>(
     new_material: Handle<StandardMaterial>,
@@ -28,19 +44,97 @@ pub fn update_material_on<E>(
     }
 }
 
-/// An observer that runs the selection event for ground
-pub fn update_ground_selection<E>(
-) -> impl Fn(Trigger<E>, Res<WaterToggle>, EventWriter<GroundSelected>, EventWriter<ManuallyIncreaseWater>)
-{
-    move |trigger, toggle, mut ground_selected, mut shift_water| {
-        if toggle.0 {
-            shift_water.send(ManuallyIncreaseWater {
-                ground: trigger.entity(),
-            });
-        } else {
+/// The material of hovered ground
+#[derive(Resource, Debug, Clone)]
+pub struct HoverMaterial(pub Handle<StandardMaterial>);
+
+/// The ground cells picked for changing all at once
+#[derive(Resource, Debug, Default)]
+pub struct SelectedCells(pub HashSet<Entity>);
+
+/// The cells of an old map are gone with it
+fn clear_selected_cells(mut event: EventReader<GenerateMap>, mut selected: ResMut<SelectedCells>) {
+    if event.read().last().is_some() {
+        selected.0.clear();
+    }
+}
+
+/// Ground with the material it shows
+type SoilMaterialQuery<'w, 's> =
+    Query<'w, 's, (&'static Soil, &'static mut MeshMaterial3d<StandardMaterial>)>;
+
+/// An observer that gives ground back the material of its soil, which may have changed since spawning
+pub fn restore_soil_material<E>() -> impl Fn(Trigger<E>, Res<SoilMaterials>, SoilMaterialQuery) {
+    move |trigger, soil_materials, mut query| {
+        if let Ok((soil, mut material)) = query.get_mut(trigger.entity()) {
+            material.0 = soil_materials.0[soil].clone();
+        }
+    }
+}
+
+/// Whether a click uses the tool or its opposite, by the action its button is bound to
+fn click_inverse(input: &ActionInput, button: PointerButton) -> Option<bool> {
+    let button = match button {
+        PointerButton::Primary => MouseButton::Left,
+        PointerButton::Secondary => MouseButton::Right,
+        PointerButton::Middle => MouseButton::Middle,
+    };
+    let action = input.mouse_action(button, &[Action::UseTool, Action::UseOpposite])?;
+    Some(action == Action::UseOpposite)
+}
+
+/// An observer that runs the selection event for clicked ground
+pub fn update_ground_selection(
+) -> impl Fn(Trigger<Pointer<Down>>, ActionInput, EventWriter<GroundSelected>) {
+    move |trigger, input, mut ground_selected| {
+        if let Some(inverse) = click_inverse(&input, trigger.event().button) {
             ground_selected.send(GroundSelected {
                 entity: trigger.entity(),
+                inverse,
             });
         }
     }
 }
+
+/// An observer that selects the ground under clicked water
+pub fn update_water_selection(
+) -> impl Fn(Trigger<Pointer<Down>>, ActionInput, Query<&Pair>, EventWriter<GroundSelected>) {
+    move |trigger, input, pairs, mut ground_selected| {
+        let Some(inverse) = click_inverse(&input, trigger.event().button) else {
+            return;
+        };
+        if let Some(pair) = pairs.iter().find(|pair| pair.water == trigger.entity()) {
+            ground_selected.send(GroundSelected {
+                entity: pair.ground,
+                inverse,
+            });
+        }
+    }
+}
+
+/// An observer that tracks the ground under the pointer, `hovering` on entering it
+pub fn update_ground_hover<E>(hovering: bool) -> impl Fn(Trigger<E>, ResMut<HoveredGround>) {
+    move |trigger, mut hovered| {
+        set_hovered(&mut hovered, trigger.entity(), hovering);
+    }
+}
+
+/// An observer that tracks the ground under hovered water
+pub fn update_water_hover<E>(
+    hovering: bool,
+) -> impl Fn(Trigger<E>, Query<&Pair>, ResMut<HoveredGround>) {
+    move |trigger, pairs, mut hovered| {
+        if let Some(pair) = pairs.iter().find(|pair| pair.water == trigger.entity()) {
+            set_hovered(&mut hovered, pair.ground, hovering);
+        }
+    }
+}
+
+fn set_hovered(hovered: &mut HoveredGround, ground: Entity, hovering: bool) {
+    if hovering {
+        hovered.0 = Some(ground);
+    } else if hovered.0 == Some(ground) {
+        //  the pointer may already be over the next cell
+        hovered.0 = None;
+    }
+}
@@ -1,73 +1,98 @@
 use bevy::prelude::*;
 
 use crate::{
-    grid::{GridCell, CELL_HEIGHT},
-    ground::Ground,
-    neighborhood::Neighborhood,
-    selection::GroundSelected,
-    water::TryShiftWater,
+    editor::GameMode, grid::GridCell, ground::Ground, neighborhood::Neighborhood, soil::Soil,
+    tuning::Tuning, water::TryShiftWater,
 };
 
-const SHIFT_RATE: f32 = 8.4;
-
 pub struct ShiftPlugin;
 
 impl Plugin for ShiftPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<ShiftFinished>().add_systems(
-            Update,
-            (try_shift_selected_cell, shift_cells, shift_neighbors),
-        );
+        app.add_event::<ShiftFinished>()
+            .add_event::<ShiftCell>()
+            .add_event::<LayerChanged>()
+            .add_systems(
+                Update,
+                (
+                    shift_cell.run_if(in_state(GameMode::Play)),
+                    shift_cells,
+                    shift_neighbors,
+                ),
+            );
     }
 }
 
+/// A ground cell held in place, neither clicks nor cascades shift it
+#[derive(Component, Debug)]
+pub struct Locked;
+
 #[derive(Component, Debug)]
 pub struct Shifting {
     pub up: bool,
+    /// whether neighbors are pulled along once the shift finishes
+    pub cascade: bool,
 }
 
 #[derive(Event)]
 pub struct ShiftFinished {
     pub entity: Entity,
     pub up: bool,
+    pub cascade: bool,
     pub layer: f32,
 }
 
-/// A function that shifts a selected cell
-fn try_shift_selected_cell(
-    mut selection: EventReader<GroundSelected>,
-    buttons: Res<ButtonInput<MouseButton>>,
-    mut cells: Query<&mut GridCell>,
+/// Shifts a ground cell by whole layers, under the same rules as a click
+#[derive(Event, Debug)]
+pub struct ShiftCell {
+    pub entity: Entity,
+    pub layers: i32,
+    pub cascade: bool,
+}
+
+/// Sent for every layer change of a ground cell, whether by the player or a cascade
+#[derive(Event, Debug)]
+pub struct LayerChanged {
+    pub change: f32,
+    pub cascaded: bool,
+}
+
+fn shift_cell(
+    mut event: EventReader<ShiftCell>,
+    tuning: Res<Tuning>,
+    mut cells: Query<&mut GridCell, (With<Ground>, Without<Locked>)>,
+    mut layer_changed: EventWriter<LayerChanged>,
     mut commands: Commands,
 ) {
-    for event in selection.read() {
-        let left_selected = buttons.pressed(MouseButton::Left);
-        let right_selected = buttons.pressed(MouseButton::Right);
-
-        if let Ok(mut cell) = cells.get_mut(event.entity) {
-            cell.layer += if left_selected {
-                CELL_HEIGHT
-            } else if right_selected {
-                -CELL_HEIGHT
-            } else {
-                0.0
-            };
+    for shift in event.read() {
+        if let Ok(mut cell) = cells.get_mut(shift.entity) {
+            let change = shift.layers as f32 * tuning.cell_height;
+            cell.layer += change;
+
+            if change != 0.0 {
+                layer_changed.send(LayerChanged {
+                    change,
+                    cascaded: false,
+                });
+            }
 
-            commands
-                .entity(event.entity)
-                .insert(Shifting { up: left_selected });
+            commands.entity(shift.entity).insert(Shifting {
+                up: shift.layers > 0,
+                cascade: shift.cascade,
+            });
         }
     }
 }
 
 fn shift_cells(
     time: Res<Time>,
+    tuning: Res<Tuning>,
     mut shifters: Query<(Entity, &GridCell, &mut Transform, &Shifting), With<Ground>>,
     mut shift_finished: EventWriter<ShiftFinished>,
     mut try_shift_water: EventWriter<TryShiftWater>,
     mut commands: Commands,
 ) {
-    let delta = SHIFT_RATE * time.delta_secs();
+    let delta = tuning.shift_rate * time.delta_secs();
     for (entity, cell, mut transform, shifting) in shifters.iter_mut() {
         if transform.translation.y != cell.layer {
             //  calculate shift and check finish
@@ -91,13 +116,14 @@ fn shift_cells(
             shift_finished.send(ShiftFinished {
                 entity,
                 up: shifting.up,
+                cascade: shifting.cascade,
                 layer: cell.layer,
             });
 
             //  send event to check the water level
             try_shift_water.send(TryShiftWater {
                 ground: entity,
-                shifting_upward: shifting.up,
+                layer: cell.layer,
             });
 
             //  remove the shifting component
@@ -108,27 +134,32 @@ fn shift_cells(
 
 fn shift_neighbors(
     mut shift_finished: EventReader<ShiftFinished>,
+    tuning: Res<Tuning>,
     cells: Query<&Neighborhood, With<Ground>>,
-    mut neighbors: Query<&mut GridCell, With<Ground>>,
+    //  only ground has soil
+    mut neighbors: Query<(&mut GridCell, &Soil), Without<Locked>>,
+    mut layer_changed: EventWriter<LayerChanged>,
     mut commands: Commands,
 ) {
-    for shift in shift_finished.read() {
+    for shift in shift_finished.read().filter(|shift| shift.cascade) {
         let Ok(neighborhood) = cells.get(shift.entity) else {
             continue;
         };
 
         //  shift the 4 neighbors, if necessary
         for neighbor_entity in neighborhood.get_neighbors().iter() {
-            let Ok(mut neighbor_cell) = neighbors.get_mut(*neighbor_entity) else {
+            let Ok((mut neighbor_cell, soil)) = neighbors.get_mut(*neighbor_entity) else {
                 continue;
             };
 
-            //  calculate if there is a layer change
+            //  calculate if there is a layer change, sturdier soils hold steeper sides
+            //  a shift of several layers pulls the neighbor just back within tolerance
             let separation = shift.layer - neighbor_cell.layer;
-            let layer_change = if shift.up && separation > CELL_HEIGHT {
-                CELL_HEIGHT
-            } else if !shift.up && separation < -CELL_HEIGHT {
-                -CELL_HEIGHT
+            let tolerance = soil.separation() as f32 * tuning.cell_height;
+            let layer_change = if shift.up && separation > tolerance {
+                separation - tolerance
+            } else if !shift.up && separation < -tolerance {
+                separation + tolerance
             } else {
                 0.0
             };
@@ -136,9 +167,14 @@ fn shift_neighbors(
             //  ensure the cell only shifts when required
             if layer_change != 0.0 {
                 neighbor_cell.layer += layer_change;
-                commands
-                    .entity(*neighbor_entity)
-                    .insert(Shifting { up: shift.up });
+                layer_changed.send(LayerChanged {
+                    change: layer_change,
+                    cascaded: true,
+                });
+                commands.entity(*neighbor_entity).insert(Shifting {
+                    up: shift.up,
+                    cascade: true,
+                });
             }
         }
     }
@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The block type of a ground cell, which decides how steep its sides may be
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Soil {
+    #[default]
+    Grass,
+    Sand,
+    Dirt,
+    Clay,
+    Rock,
+}
+
+impl Soil {
+    pub const ALL: [Soil; 5] = [Soil::Grass, Soil::Sand, Soil::Dirt, Soil::Clay, Soil::Rock];
+
+    /// The number of layers a neighbor may differ by before this cell is pulled along
+    pub fn separation(&self) -> i32 {
+        match self {
+            Soil::Grass | Soil::Sand => 1,
+            Soil::Dirt => 2,
+            Soil::Clay => 3,
+            Soil::Rock => 4,
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            Soil::Grass => Color::srgb(0.0, 0.9, 0.1),
+            Soil::Sand => Color::srgb(0.9, 0.8, 0.5),
+            Soil::Dirt => Color::srgb(0.45, 0.3, 0.15),
+            Soil::Clay => Color::srgb(0.7, 0.35, 0.2),
+            Soil::Rock => Color::srgb(0.5, 0.5, 0.5),
+        }
+    }
+
+    pub fn next(&self) -> Soil {
+        let index = Soil::ALL.iter().position(|soil| soil == self).unwrap_or(0);
+        Soil::ALL[(index + 1) % Soil::ALL.len()]
+    }
+}
+
+/// The material of each soil, shared by every ground cell made of it
+#[derive(Resource, Debug, Clone)]
+pub struct SoilMaterials(pub HashMap<Soil, Handle<StandardMaterial>>);
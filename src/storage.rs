@@ -0,0 +1,695 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{map::MapGenerationSettings, soil::Soil};
+
+pub const MAPS_DIRECTORY: &str = "./assets/maps";
+pub const STAMPS_DIRECTORY: &str = "./assets/stamps";
+/// The maps folder relative to the asset root
+pub const MAPS_ASSET_PATH: &str = "maps";
+
+/// The newest map format this build understands, files without a version are 0
+pub const MAP_FORMAT_VERSION: u64 = 1;
+
+/// Upgrades, in order, that take a map from version `index` to `index + 1`
+const MIGRATIONS: [fn(&mut Value); MAP_FORMAT_VERSION as usize] = [migrate_unversioned];
+
+pub struct StoragePlugin;
+
+impl Plugin for StoragePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<MapAsset>()
+            .register_asset_loader(MapAssetLoader);
+    }
+}
+
+/// A map file loaded through the asset server, so it is reloaded when it changes
+#[derive(Asset, TypePath, Debug)]
+pub struct MapAsset {
+    pub settings: MapGenerationSettings,
+}
+
+#[derive(Default)]
+struct MapAssetLoader;
+
+impl AssetLoader for MapAssetLoader {
+    type Asset = MapAsset;
+    type Settings = ();
+    type Error = MapStorageError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<MapAsset, MapStorageError> {
+        let path = load_context.path().to_path_buf();
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|e| MapStorageError::from_io(&path, e))?;
+
+        let format = MapFormat::from_path(&path).unwrap_or_default();
+        let (_, settings) = parse_map(&path, &bytes, format)?;
+        Ok(MapAsset { settings })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["json", "ron", "bin"]
+    }
+}
+
+/// How a map file is encoded, chosen by its extension
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MapFormat {
+    #[default]
+    Json,
+    /// for editing maps by hand
+    Ron,
+    /// compact MessagePack, for large maps
+    Binary,
+}
+
+impl MapFormat {
+    pub const ALL: [MapFormat; 3] = [MapFormat::Json, MapFormat::Ron, MapFormat::Binary];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            MapFormat::Json => "json",
+            MapFormat::Ron => "ron",
+            MapFormat::Binary => "bin",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<MapFormat> {
+        MapFormat::ALL
+            .into_iter()
+            .find(|format| format.extension() == extension)
+    }
+
+    pub fn from_path(path: &Path) -> Option<MapFormat> {
+        MapFormat::from_extension(path.extension()?.to_str()?)
+    }
+}
+
+/// Only the version of a map, read before the rest of the layout
+#[derive(Deserialize)]
+struct VersionHeader {
+    #[serde(default)]
+    version: u64,
+}
+
+/// A map file as shown in listings
+#[derive(Debug)]
+pub struct MapInfo {
+    pub modified: Option<SystemTime>,
+    pub settings: MapGenerationSettings,
+}
+
+#[derive(Debug)]
+pub enum MapStorageError {
+    NotFound(PathBuf),
+    AlreadyExists(PathBuf),
+    PermissionDenied(PathBuf),
+    Parse {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    UnsupportedVersion {
+        path: PathBuf,
+        version: u64,
+    },
+    /// a name that would reach outside its folder
+    InvalidName(String),
+    Serialize(String),
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+}
+
+impl fmt::Display for MapStorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapStorageError::NotFound(path) => write!(f, "file {:?} not found", path),
+            MapStorageError::AlreadyExists(path) => write!(f, "file {:?} already exists", path),
+            MapStorageError::PermissionDenied(path) => {
+                write!(f, "permission denied for file {:?}", path)
+            }
+            //  binary files have no lines to point at
+            MapStorageError::Parse {
+                path,
+                line: 0,
+                message,
+                ..
+            } => write!(f, "{:?}: {}", path, message),
+            MapStorageError::Parse {
+                path,
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "{:?} line {}, column {}: {}",
+                path, line, column, message
+            ),
+            MapStorageError::UnsupportedVersion { path, version } => write!(
+                f,
+                "{:?} has format version {}, newest supported is {}",
+                path, version, MAP_FORMAT_VERSION
+            ),
+            MapStorageError::InvalidName(name) => {
+                write!(
+                    f,
+                    "{:?} is not a valid name, it must stay in its folder",
+                    name
+                )
+            }
+            MapStorageError::Serialize(message) => {
+                write!(f, "failed to serialize: {}", message)
+            }
+            MapStorageError::Io { path, error } => write!(f, "{:?}: {}", path, error),
+        }
+    }
+}
+
+impl std::error::Error for MapStorageError {}
+
+impl MapStorageError {
+    fn from_io(path: &Path, error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => MapStorageError::NotFound(path.to_path_buf()),
+            io::ErrorKind::PermissionDenied => {
+                MapStorageError::PermissionDenied(path.to_path_buf())
+            }
+            _ => MapStorageError::Io {
+                path: path.to_path_buf(),
+                error,
+            },
+        }
+    }
+
+    fn from_json(path: &Path, error: serde_json::Error) -> Self {
+        MapStorageError::Parse {
+            path: path.to_path_buf(),
+            line: error.line(),
+            column: error.column(),
+            message: error.to_string(),
+        }
+    }
+
+    fn from_ron(path: &Path, error: ron::error::SpannedError) -> Self {
+        MapStorageError::Parse {
+            path: path.to_path_buf(),
+            line: error.position.line,
+            column: error.position.col,
+            message: error.code.to_string(),
+        }
+    }
+
+    fn from_message(path: &Path, error: impl fmt::Display) -> Self {
+        MapStorageError::Parse {
+            path: path.to_path_buf(),
+            line: 0,
+            column: 0,
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Names are plain file names, so none can climb out of the maps or stamps folder
+pub fn check_name(name: &str) -> Result<(), MapStorageError> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        return Err(MapStorageError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+/// The file of a map, a name without extension picks the first format that exists
+pub fn map_path(directory: &Path, name: &str) -> PathBuf {
+    let path = directory.join(name);
+    if MapFormat::from_path(&path).is_some() {
+        return path;
+    }
+
+    MapFormat::ALL
+        .into_iter()
+        .map(|format| directory.join(format!("{}.{}", name, format.extension())))
+        .find(|candidate| candidate.exists())
+        .unwrap_or_else(|| directory.join(format!("{}.{}", name, MapFormat::Json.extension())))
+}
+
+/// The path of a map for the asset server
+pub fn map_asset_path(name: &str) -> String {
+    let path = map_path(Path::new(MAPS_DIRECTORY), name);
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    format!("{}/{}", MAPS_ASSET_PATH, file_name)
+}
+
+pub fn load_map(directory: &Path, name: &str) -> Result<MapGenerationSettings, MapStorageError> {
+    read_map(directory, name).map(|(_, settings)| settings)
+}
+
+/// Reads a map of any supported version, returning the version it was stored as
+fn read_map(directory: &Path, name: &str) -> Result<(u64, MapGenerationSettings), MapStorageError> {
+    check_name(name)?;
+    let path = map_path(directory, name);
+    let bytes = fs::read(&path).map_err(|e| MapStorageError::from_io(&path, e))?;
+    parse_map(
+        &path,
+        &bytes,
+        MapFormat::from_path(&path).unwrap_or_default(),
+    )
+}
+
+fn parse_map(
+    path: &Path,
+    bytes: &[u8],
+    format: MapFormat,
+) -> Result<(u64, MapGenerationSettings), MapStorageError> {
+    //  check the version before the layout, older and newer files may not match it
+    let version = decode::<VersionHeader>(path, bytes, format)?.version;
+    if version > MAP_FORMAT_VERSION {
+        return Err(MapStorageError::UnsupportedVersion {
+            path: path.to_path_buf(),
+            version,
+        });
+    }
+
+    //  RON has no generic value that keeps enum variant names, so it cannot go through the
+    //  JSON migrations. RON arrived with version 1, whose only migration adds the version
+    //  field, so reading it as the newest layout is exact. A migration that changes the
+    //  layout needs its own RON path.
+    let mut settings: MapGenerationSettings =
+        if version == MAP_FORMAT_VERSION || format == MapFormat::Ron {
+            decode(path, bytes, format)?
+        } else {
+            let mut value: Value = decode(path, bytes, format)?;
+            for migration in &MIGRATIONS[version as usize..] {
+                migration(&mut value);
+            }
+            serde_json::from_value(value).map_err(|e| MapStorageError::from_json(path, e))?
+        };
+
+    settings.version = MAP_FORMAT_VERSION;
+    Ok((version, settings))
+}
+
+fn decode<T: for<'de> Deserialize<'de>>(
+    path: &Path,
+    bytes: &[u8],
+    format: MapFormat,
+) -> Result<T, MapStorageError> {
+    match format {
+        MapFormat::Json => {
+            serde_json::from_slice(bytes).map_err(|e| MapStorageError::from_json(path, e))
+        }
+        MapFormat::Ron => {
+            let text =
+                std::str::from_utf8(bytes).map_err(|e| MapStorageError::from_message(path, e))?;
+            ron::from_str(text).map_err(|e| MapStorageError::from_ron(path, e))
+        }
+        MapFormat::Binary => {
+            rmp_serde::from_slice(bytes).map_err(|e| MapStorageError::from_message(path, e))
+        }
+    }
+}
+
+fn encode(settings: &MapGenerationSettings, format: MapFormat) -> Result<Vec<u8>, MapStorageError> {
+    let serialize_error = |e: &dyn fmt::Display| MapStorageError::Serialize(e.to_string());
+
+    match format {
+        MapFormat::Json => serde_json::to_vec_pretty(settings).map_err(|e| serialize_error(&e)),
+        MapFormat::Ron => ron::ser::to_string_pretty(settings, ron::ser::PrettyConfig::default())
+            .map(String::into_bytes)
+            .map_err(|e| serialize_error(&e)),
+        //  fields are written by name, so binary maps can still be migrated
+        MapFormat::Binary => rmp_serde::to_vec_named(settings).map_err(|e| serialize_error(&e)),
+    }
+}
+
+/// Writes a map in the format of its extension, JSON when it has none
+pub fn save_map(
+    directory: &Path,
+    name: &str,
+    settings: &MapGenerationSettings,
+) -> Result<PathBuf, MapStorageError> {
+    check_name(name)?;
+    let mut path = directory.join(name);
+    let format = MapFormat::from_path(&path).unwrap_or_else(|| {
+        path = directory.join(format!("{}.{}", name, MapFormat::Json.extension()));
+        MapFormat::Json
+    });
+
+    let bytes = encode(settings, format)?;
+    fs::write(&path, bytes).map_err(|e| MapStorageError::from_io(&path, e))?;
+    Ok(path)
+}
+
+/// Writes a copy of a map in another format, next to the original
+pub fn convert_map(
+    directory: &Path,
+    name: &str,
+    format: MapFormat,
+) -> Result<PathBuf, MapStorageError> {
+    let source = map_path(directory, name);
+    let settings = load_map(directory, name)?;
+
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+    save_map(
+        directory,
+        &format!("{}.{}", stem, format.extension()),
+        &settings,
+    )
+}
+
+pub fn map_info(directory: &Path, name: &str) -> Result<MapInfo, MapStorageError> {
+    let path = map_path(directory, name);
+    let settings = load_map(directory, name)?;
+    let modified = fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+
+    Ok(MapInfo { modified, settings })
+}
+
+pub fn delete_map(directory: &Path, name: &str) -> Result<PathBuf, MapStorageError> {
+    check_name(name)?;
+    let path = map_path(directory, name);
+    fs::remove_file(&path).map_err(|e| MapStorageError::from_io(&path, e))?;
+    Ok(path)
+}
+
+/// Renames a map file, keeping its format when the new name has no extension
+pub fn rename_map(directory: &Path, from: &str, to: &str) -> Result<PathBuf, MapStorageError> {
+    check_name(from)?;
+    check_name(to)?;
+    let source = map_path(directory, from);
+    if !source.exists() {
+        return Err(MapStorageError::NotFound(source));
+    }
+
+    let format = MapFormat::from_path(&source).unwrap_or_default();
+    let mut target = directory.join(to);
+    if MapFormat::from_path(&target).is_none() {
+        target = directory.join(format!("{}.{}", to, format.extension()));
+    }
+    if target.exists() {
+        return Err(MapStorageError::AlreadyExists(target));
+    }
+
+    if MapFormat::from_path(&target) == Some(format) {
+        fs::rename(&source, &target).map_err(|e| MapStorageError::from_io(&source, e))?;
+        return Ok(target);
+    }
+
+    //  a new extension means a new format, so the map is written again
+    let settings = load_map(directory, from)?;
+    let file_name = target.file_name().unwrap_or_default().to_string_lossy();
+    let target = save_map(directory, &file_name, &settings)?;
+    delete_map(directory, from)?;
+    Ok(target)
+}
+
+/// Rewrites a map file in the newest version, returning the version it had before
+pub fn upgrade_map(directory: &Path, name: &str) -> Result<u64, MapStorageError> {
+    let path = map_path(directory, name);
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    let (version, settings) = read_map(directory, &file_name)?;
+    if version < MAP_FORMAT_VERSION {
+        save_map(directory, &file_name, &settings)?;
+    }
+    Ok(version)
+}
+
+/// The file names of all maps in the directory, sorted
+pub fn map_names(directory: &Path) -> Result<Vec<String>, MapStorageError> {
+    let entries = fs::read_dir(directory).map_err(|e| MapStorageError::from_io(directory, e))?;
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| MapFormat::from_path(path).is_some())
+        .filter_map(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// A copied region of terrain, relative to its center cell
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stamp {
+    pub cells: Vec<StampCell>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StampCell {
+    /// rows and columns from the center
+    pub offset: IVec2,
+    /// layers above the center, negative below it
+    pub layer: i32,
+    pub soil: Soil,
+    /// height of the water on the cell
+    pub water: f32,
+}
+
+/// The file of a stamp, stamps are always JSON
+pub fn stamp_path(directory: &Path, name: &str) -> Result<PathBuf, MapStorageError> {
+    check_name(name)?;
+    let extension = MapFormat::Json.extension();
+    Ok(match Path::new(name).extension() {
+        Some(given) if given == extension => directory.join(name),
+        _ => directory.join(format!("{}.{}", name, extension)),
+    })
+}
+
+pub fn load_stamp(directory: &Path, name: &str) -> Result<Stamp, MapStorageError> {
+    let path = stamp_path(directory, name)?;
+    let bytes = fs::read(&path).map_err(|e| MapStorageError::from_io(&path, e))?;
+    serde_json::from_slice(&bytes).map_err(|e| MapStorageError::from_json(&path, e))
+}
+
+/// Writes a stamp, making the stamps folder on first use
+pub fn save_stamp(directory: &Path, name: &str, stamp: &Stamp) -> Result<PathBuf, MapStorageError> {
+    let path = stamp_path(directory, name)?;
+    fs::create_dir_all(directory).map_err(|e| MapStorageError::from_io(directory, e))?;
+
+    let bytes =
+        serde_json::to_vec_pretty(stamp).map_err(|e| MapStorageError::Serialize(e.to_string()))?;
+    fs::write(&path, bytes).map_err(|e| MapStorageError::from_io(&path, e))?;
+    Ok(path)
+}
+
+/// The names of all stamps in the directory, without extension, sorted
+pub fn stamp_names(directory: &Path) -> Result<Vec<String>, MapStorageError> {
+    let entries = fs::read_dir(directory).map_err(|e| MapStorageError::from_io(directory, e))?;
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| MapFormat::from_path(path) == Some(MapFormat::Json))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Version 1 only added the version field, the layout is unchanged
+fn migrate_unversioned(value: &mut Value) {
+    if let Value::Object(map) = value {
+        map.insert(String::from("version"), Value::from(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh folder under the system temp directory, removed when dropped
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("hill-builder-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn as_json(settings: &MapGenerationSettings) -> Value {
+        serde_json::to_value(settings).unwrap()
+    }
+
+    #[test]
+    fn saved_map_loads_unchanged() {
+        let directory = TempDir::new("round-trip");
+        let settings = MapGenerationSettings::default();
+
+        let path = save_map(&directory.0, "level", &settings).unwrap();
+        assert_eq!(path, directory.0.join("level.json"));
+        let loaded = load_map(&directory.0, "level").unwrap();
+        assert_eq!(as_json(&loaded), as_json(&settings));
+    }
+
+    #[test]
+    fn formats_convert_without_loss() {
+        let directory = TempDir::new("convert");
+
+        //  the shipped maps, plus one with hand-set layers and water
+        let mut maps: Vec<(String, MapGenerationSettings)> = map_names(Path::new(MAPS_DIRECTORY))
+            .unwrap()
+            .into_iter()
+            .map(|name| {
+                let settings = load_map(Path::new(MAPS_DIRECTORY), &name).unwrap();
+                let stem = Path::new(&name).file_stem().unwrap().to_string_lossy();
+                (stem.into_owned(), settings)
+            })
+            .collect();
+        let mut custom = MapGenerationSettings::default();
+        custom.make_custom();
+        custom.set_layer(IVec2::new(1, 2), 3);
+        custom.set_water(IVec2::new(2, 1), 0.75);
+        maps.push((String::from("custom"), custom));
+
+        for (stem, original) in maps {
+            save_map(&directory.0, &format!("{}.json", stem), &original).unwrap();
+
+            //  JSON to RON, RON to MessagePack, and MessagePack back to JSON
+            convert_map(&directory.0, &format!("{}.json", stem), MapFormat::Ron).unwrap();
+            convert_map(&directory.0, &format!("{}.ron", stem), MapFormat::Binary).unwrap();
+            fs::remove_file(directory.0.join(format!("{}.json", stem))).unwrap();
+            convert_map(&directory.0, &format!("{}.bin", stem), MapFormat::Json).unwrap();
+
+            for format in MapFormat::ALL {
+                let file_name = format!("{}.{}", stem, format.extension());
+                let converted = load_map(&directory.0, &file_name).unwrap();
+                assert_eq!(as_json(&converted), as_json(&original), "{}", file_name);
+            }
+        }
+    }
+
+    #[test]
+    fn unversioned_ron_map_loads() {
+        let directory = TempDir::new("ron-version");
+        let settings = MapGenerationSettings::default();
+        let text = ron::ser::to_string(&settings).unwrap().replacen(
+            &format!("version:{},", MAP_FORMAT_VERSION),
+            "",
+            1,
+        );
+        assert!(!text.contains("version"));
+        fs::write(directory.0.join("old.ron"), text).unwrap();
+
+        let loaded = load_map(&directory.0, "old.ron").unwrap();
+        assert_eq!(as_json(&loaded), as_json(&settings));
+    }
+
+    #[test]
+    fn missing_map_is_not_found() {
+        let directory = TempDir::new("not-found");
+
+        let error = load_map(&directory.0, "missing").unwrap_err();
+        assert!(matches!(error, MapStorageError::NotFound(path) if path.ends_with("missing.json")));
+    }
+
+    #[test]
+    fn parse_error_points_at_line_and_column() {
+        let directory = TempDir::new("parse");
+        fs::write(directory.0.join("broken.json"), "{\n  \"size\": ,\n}").unwrap();
+
+        match load_map(&directory.0, "broken").unwrap_err() {
+            MapStorageError::Parse { line, column, .. } => {
+                assert_eq!(line, 2);
+                assert_eq!(column, 11);
+            }
+            error => panic!("expected a parse error, got {:?}", error),
+        }
+    }
+
+    #[test]
+    fn newer_version_is_unsupported() {
+        let directory = TempDir::new("version");
+        let version = MAP_FORMAT_VERSION + 1;
+        fs::write(
+            directory.0.join("future.json"),
+            format!("{{\"version\": {}}}", version),
+        )
+        .unwrap();
+
+        let error = load_map(&directory.0, "future").unwrap_err();
+        assert!(matches!(
+            error,
+            MapStorageError::UnsupportedVersion { version: found, .. } if found == version
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unreadable_map_is_permission_denied() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory = TempDir::new("permission");
+        let path = save_map(&directory.0, "private", &MapGenerationSettings::default()).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o000)).unwrap();
+
+        //  root reads the file regardless, leaving nothing to check
+        if fs::read(&path).is_ok() {
+            return;
+        }
+        let error = load_map(&directory.0, "private").unwrap_err();
+        assert!(matches!(error, MapStorageError::PermissionDenied(_)));
+    }
+
+    #[test]
+    fn io_errors_are_sorted_by_kind() {
+        let path = Path::new("map.json");
+
+        let denied = MapStorageError::from_io(path, io::ErrorKind::PermissionDenied.into());
+        assert!(matches!(denied, MapStorageError::PermissionDenied(_)));
+        let missing = MapStorageError::from_io(path, io::ErrorKind::NotFound.into());
+        assert!(matches!(missing, MapStorageError::NotFound(_)));
+    }
+
+    #[test]
+    fn names_cannot_leave_their_folder() {
+        let directory = TempDir::new("names");
+        let settings = MapGenerationSettings::default();
+
+        for name in ["../escape", "nested/map", "..", "back\\slash", ""] {
+            assert!(matches!(
+                save_map(&directory.0, name, &settings),
+                Err(MapStorageError::InvalidName(_))
+            ));
+            assert!(matches!(
+                load_map(&directory.0, name),
+                Err(MapStorageError::InvalidName(_))
+            ));
+            assert!(matches!(
+                stamp_path(&directory.0, name),
+                Err(MapStorageError::InvalidName(_))
+            ));
+        }
+    }
+}
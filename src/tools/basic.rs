@@ -0,0 +1,273 @@
+use bevy::prelude::*;
+
+use crate::{
+    grid::GridCell,
+    neighborhood::Neighborhood,
+    pair::Pair,
+    shifting::ShiftCell,
+    soil::Soil,
+    tuning::Tuning,
+    water::{ManuallyIncreaseWater, Water},
+};
+
+use super::{brush::Brush, Tool, ToolStatus, ToolTarget};
+
+/// Pulls cells up, dragging their neighbors along
+pub struct RaiseTool;
+
+impl Tool for RaiseTool {
+    fn name(&self) -> &'static str {
+        "raise"
+    }
+
+    fn apply(&self, world: &mut World, target: &ToolTarget) {
+        let layers = strength(world) * if target.inverse { -1 } else { 1 };
+        for ground in target.cells.iter() {
+            shift(world, *ground, layers);
+        }
+    }
+}
+
+/// Pushes cells down, dragging their neighbors along
+pub struct LowerTool;
+
+impl Tool for LowerTool {
+    fn name(&self) -> &'static str {
+        "lower"
+    }
+
+    fn apply(&self, world: &mut World, target: &ToolTarget) {
+        let layers = strength(world) * if target.inverse { 1 } else { -1 };
+        for ground in target.cells.iter() {
+            shift(world, *ground, layers);
+        }
+    }
+}
+
+/// Pours layers of water onto cells
+pub struct AddWaterTool;
+
+impl Tool for AddWaterTool {
+    fn name(&self) -> &'static str {
+        "add water"
+    }
+
+    fn apply(&self, world: &mut World, target: &ToolTarget) {
+        let layers = strength(world) as f32 * if target.inverse { -1.0 } else { 1.0 };
+        for ground in target.cells.iter() {
+            pour(world, *ground, layers);
+        }
+    }
+}
+
+/// Takes layers of water from cells
+pub struct RemoveWaterTool;
+
+impl Tool for RemoveWaterTool {
+    fn name(&self) -> &'static str {
+        "remove water"
+    }
+
+    fn apply(&self, world: &mut World, target: &ToolTarget) {
+        let layers = strength(world) as f32 * if target.inverse { 1.0 } else { -1.0 };
+        for ground in target.cells.iter() {
+            pour(world, *ground, layers);
+        }
+    }
+}
+
+/// Brings cells to the brush target, or the clicked cell's layer
+pub struct FlattenTool;
+
+impl Tool for FlattenTool {
+    fn name(&self) -> &'static str {
+        "flatten"
+    }
+
+    fn apply(&self, world: &mut World, target: &ToolTarget) {
+        let Some(center) = layer_of(world, target.center) else {
+            return;
+        };
+        let flatten_to = world.resource::<Brush>().target;
+
+        //  a single cell is already level with itself, so level its neighbors instead
+        let cells = if target.cells.len() == 1 && flatten_to.is_none() {
+            neighbor_layers(world, target.center)
+        } else {
+            target
+                .cells
+                .iter()
+                .filter_map(|ground| Some((*ground, layer_of(world, *ground)?)))
+                .collect()
+        };
+
+        let level = flatten_to.unwrap_or(center);
+        for (ground, layer) in cells {
+            if layer != level {
+                shift(world, ground, level - layer);
+            }
+        }
+    }
+}
+
+/// Moves cells toward the average of their neighbors
+pub struct SmoothTool;
+
+impl Tool for SmoothTool {
+    fn name(&self) -> &'static str {
+        "smooth"
+    }
+
+    fn apply(&self, world: &mut World, target: &ToolTarget) {
+        let strength = strength(world);
+
+        //  every step is worked out before any cell moves
+        let steps: Vec<(Entity, i32)> = target
+            .cells
+            .iter()
+            .filter_map(|ground| {
+                let layer = layer_of(world, *ground)?;
+                let neighbors = neighbor_layers(world, *ground);
+                if neighbors.is_empty() {
+                    return None;
+                }
+                let average = neighbors
+                    .iter()
+                    .map(|(_, layer)| *layer as f32)
+                    .sum::<f32>()
+                    / neighbors.len() as f32;
+                let step = ((average - layer as f32).round() as i32).clamp(-strength, strength);
+                Some((*ground, step))
+            })
+            .collect();
+
+        for (ground, step) in steps {
+            if step != 0 {
+                shift(world, ground, step);
+            }
+        }
+    }
+}
+
+/// Roughens cells by a random number of layers, up to the brush strength
+pub struct NoiseTool;
+
+impl Tool for NoiseTool {
+    fn name(&self) -> &'static str {
+        "noise"
+    }
+
+    fn apply(&self, world: &mut World, target: &ToolTarget) {
+        let strength = strength(world);
+        let seed = world.resource::<Time>().elapsed().as_nanos() as u32;
+
+        for ground in target.cells.iter() {
+            let Some(cell) = world
+                .get::<GridCell>(*ground)
+                .map(|cell| cell.coordinates())
+            else {
+                continue;
+            };
+            let range = (2 * strength + 1) as u32;
+            let layers = (hash(cell, seed) % range) as i32 - strength;
+            if layers != 0 {
+                shift(world, *ground, layers);
+            }
+        }
+    }
+}
+
+/// Shows the clicked cell's layer, soil and water under the toolbar
+pub struct InspectTool;
+
+impl Tool for InspectTool {
+    fn name(&self) -> &'static str {
+        "inspect"
+    }
+
+    fn edits(&self, _world: &World) -> bool {
+        false
+    }
+
+    fn apply(&self, world: &mut World, target: &ToolTarget) {
+        let ground = target.center;
+        let cell_height = world.resource::<Tuning>().cell_height;
+        let Some(cell) = world.get::<GridCell>(ground) else {
+            return;
+        };
+        let mut status = format!(
+            "cell {}, {}: layer {}",
+            cell.row,
+            cell.col,
+            cell.layer_index(cell_height)
+        );
+
+        if let Some(soil) = world.get::<Soil>(ground) {
+            status += &format!(", {:?}", soil);
+        }
+        if let Some(water) = water_of(world, ground).and_then(|water| world.get::<Water>(water)) {
+            status += &format!(", water {:.2}", water.amount);
+        }
+
+        world.resource_mut::<ToolStatus>().0 = status;
+    }
+}
+
+fn strength(world: &World) -> i32 {
+    world.resource::<Brush>().strength
+}
+
+/// Shifts a cell under the same rules as a click
+fn shift(world: &mut World, ground: Entity, layers: i32) {
+    world.send_event(ShiftCell {
+        entity: ground,
+        layers,
+        cascade: true,
+    });
+}
+
+/// Adds or removes whole layers of water
+fn pour(world: &mut World, ground: Entity, layers: f32) {
+    let amount = layers * world.resource::<Tuning>().cell_height;
+    world.send_event(ManuallyIncreaseWater { ground, amount });
+}
+
+fn layer_of(world: &World, ground: Entity) -> Option<i32> {
+    let cell_height = world.resource::<Tuning>().cell_height;
+    world
+        .get::<GridCell>(ground)
+        .map(|cell| cell.layer_index(cell_height))
+}
+
+/// The ground cells next to a cell, with their layers
+fn neighbor_layers(world: &World, ground: Entity) -> Vec<(Entity, i32)> {
+    let Some(neighborhood) = world.get::<Neighborhood>(ground) else {
+        return Vec::new();
+    };
+
+    neighborhood
+        .get_neighbors()
+        .into_iter()
+        .filter_map(|neighbor| Some((neighbor, layer_of(world, neighbor)?)))
+        .collect()
+}
+
+fn water_of(world: &mut World, ground: Entity) -> Option<Entity> {
+    world
+        .query::<&Pair>()
+        .iter(world)
+        .find(|pair| pair.ground == ground)
+        .map(|pair| pair.water)
+}
+
+/// A cheap, well mixed hash, so each cell gets its own noise
+fn hash(cell: IVec2, seed: u32) -> u32 {
+    let mut value = (cell.x as u32).wrapping_mul(0x9E37_79B1)
+        ^ (cell.y as u32).wrapping_mul(0x85EB_CA77)
+        ^ seed;
+    value ^= value >> 15;
+    value = value.wrapping_mul(0x2C1B_3C6D);
+    value ^= value >> 12;
+    value = value.wrapping_mul(0x297A_2D39);
+    value ^ (value >> 15)
+}
@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+
+use crate::{
+    bindings::{Action, ActionInput},
+    editor::GameMode,
+    grid::GridCell,
+    ground::Ground,
+};
+
+pub const BRUSH_RADIUS_MAX: i32 = 8;
+pub const BRUSH_STRENGTH_MAX: i32 = 4;
+/// uses per second while held
+pub const BRUSH_REPEAT_MAX: f32 = 30.0;
+
+pub struct BrushPlugin;
+
+impl Plugin for BrushPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Brush::default());
+
+        app.add_systems(Update, brush_keys.run_if(in_state(GameMode::Play)));
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BrushShape {
+    #[default]
+    Square,
+    Circle,
+}
+
+impl BrushShape {
+    pub const ALL: [BrushShape; 2] = [BrushShape::Square, BrushShape::Circle];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BrushShape::Square => "square",
+            BrushShape::Circle => "circle",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|shape| shape.name() == name)
+    }
+
+    pub fn next(&self) -> BrushShape {
+        let index = Self::ALL
+            .iter()
+            .position(|shape| shape == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Whether an offset from the brush center is under the brush
+    fn contains(&self, offset: IVec2, radius: i32) -> bool {
+        match self {
+            BrushShape::Square => offset.abs().max_element() <= radius,
+            //  the half cell keeps small circles from being diamonds
+            BrushShape::Circle => (offset.length_squared() as f32) <= (radius as f32 + 0.5).powi(2),
+        }
+    }
+}
+
+/// The area tools work on around the clicked cell
+#[derive(Resource, Debug, Clone)]
+pub struct Brush {
+    pub shape: BrushShape,
+    /// cells from the center to the edge, 0 for a single cell
+    pub radius: i32,
+    /// layers changed by each use
+    pub strength: i32,
+    /// the layer to flatten to, the clicked cell's layer when unset
+    pub target: Option<i32>,
+    /// uses per second while held over one cell, 0 to use it once
+    pub repeat: f32,
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Self {
+            shape: BrushShape::default(),
+            radius: 0,
+            strength: 1,
+            target: None,
+            repeat: 0.0,
+        }
+    }
+}
+
+impl Brush {
+    pub fn describe(&self) -> String {
+        let mut description = format!(
+            "{} brush, radius {}, strength {}",
+            self.shape.name(),
+            self.radius,
+            self.strength
+        );
+        if let Some(target) = self.target {
+            description += &format!(", flatten to {}", target);
+        }
+        if self.repeat > 0.0 {
+            description += &format!(", repeating {}/s", self.repeat);
+        }
+        description
+    }
+}
+
+/// The ground cells under the brush, the center first
+pub fn footprint(world: &mut World, center: Entity) -> Vec<Entity> {
+    let brush = world.resource::<Brush>().clone();
+    let Some(center_cell) = world.get::<GridCell>(center).map(|cell| cell.coordinates()) else {
+        return Vec::new();
+    };
+
+    let mut cells = vec![center];
+    cells.extend(
+        world
+            .query_filtered::<(Entity, &GridCell), With<Ground>>()
+            .iter(world)
+            .filter(|(entity, cell)| {
+                *entity != center
+                    && brush
+                        .shape
+                        .contains(cell.coordinates() - center_cell, brush.radius)
+            })
+            .map(|(entity, _)| entity),
+    );
+    cells
+}
+
+fn brush_keys(input: ActionInput, mut brush: ResMut<Brush>) {
+    if input.just_pressed(Action::BrushSmaller) {
+        brush.radius = (brush.radius - 1).max(0);
+    }
+    if input.just_pressed(Action::BrushLarger) {
+        brush.radius = (brush.radius + 1).min(BRUSH_RADIUS_MAX);
+    }
+    if input.just_pressed(Action::BrushWeaker) {
+        brush.strength = (brush.strength - 1).max(1);
+    }
+    if input.just_pressed(Action::BrushStronger) {
+        brush.strength = (brush.strength + 1).min(BRUSH_STRENGTH_MAX);
+    }
+    if input.just_pressed(Action::BrushShape) {
+        brush.shape = brush.shape.next();
+    }
+}
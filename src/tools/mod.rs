@@ -0,0 +1,439 @@
+mod basic;
+pub mod brush;
+pub mod path;
+pub mod select;
+pub mod stamp;
+pub mod undo;
+
+use std::{collections::HashSet, sync::Arc};
+
+use bevy::prelude::*;
+
+use crate::{
+    bindings::{input_unclaimed, Action, ActionInput, Bindings},
+    campaign::LevelSelectOpen,
+    editor::GameMode,
+    scoring::EditBudget,
+    selection::{GroundSelected, HoveredGround},
+};
+use basic::{
+    AddWaterTool, FlattenTool, InspectTool, LowerTool, NoiseTool, RaiseTool, RemoveWaterTool,
+    SmoothTool,
+};
+use brush::{Brush, BrushPlugin};
+use path::{PathPlugin, PathTool};
+use select::{SelectPlugin, SelectTool};
+use stamp::{CopyTool, PasteTool, StampPlugin};
+use undo::UndoPlugin;
+
+const TOOLBAR_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.8);
+const ACTIVE_TOOL_COLOR: Color = Color::srgb(0.2, 0.4, 0.8);
+
+/// The most times a repeating brush is used in one frame, however long the frame took
+const REPEAT_USES_MAX: u32 = 2;
+
+pub struct ToolsPlugin;
+
+impl Plugin for ToolsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ToolPalette::default())
+            .insert_resource(ToolStatus::default())
+            .insert_resource(Stroke::default());
+
+        app.add_plugins((
+            BrushPlugin,
+            UndoPlugin,
+            PathPlugin,
+            StampPlugin,
+            SelectPlugin,
+        ));
+
+        app.add_tool(Action::Slot1, RaiseTool)
+            .add_tool(Action::Slot2, LowerTool)
+            .add_tool(Action::Slot3, AddWaterTool)
+            .add_tool(Action::Slot4, RemoveWaterTool)
+            .add_tool(Action::Slot5, FlattenTool)
+            .add_tool(Action::Slot6, SmoothTool)
+            .add_tool(Action::Slot7, NoiseTool)
+            .add_tool(Action::Slot8, PathTool)
+            .add_tool(Action::Slot9, InspectTool)
+            .add_tool(Action::Slot10, SelectTool)
+            .add_tool(Action::CopyTool, CopyTool)
+            .add_tool(Action::PasteTool, PasteTool);
+
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (
+                (
+                    tool_keys,
+                    toolbar_buttons.run_if(input_unclaimed),
+                    start_stroke,
+                    continue_stroke,
+                )
+                    .chain()
+                    .run_if(in_state(GameMode::Play)),
+                update_toolbar,
+                update_tool_labels.run_if(resource_changed::<Bindings>),
+                update_brush_display,
+            ),
+        );
+    }
+}
+
+/// Something the player does to the cells they click
+pub trait Tool: Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+
+    /// Whether using the tool now changes the map, and so can be undone
+    fn edits(&self, _world: &World) -> bool {
+        true
+    }
+
+    /// Whether dragging uses the tool on every cell passed over
+    fn drags(&self) -> bool {
+        true
+    }
+
+    fn apply(&self, world: &mut World, target: &ToolTarget);
+}
+
+/// The cells a tool is used on
+#[derive(Debug)]
+pub struct ToolTarget {
+    /// the clicked ground cell
+    pub center: Entity,
+    /// the ground cells under the brush, the center first
+    pub cells: Vec<Entity>,
+    /// whether the right button was used, asking for the tool's opposite
+    pub inverse: bool,
+}
+
+/// Registers tools with the palette, in toolbar order
+pub trait AddTool {
+    fn add_tool(&mut self, action: Action, tool: impl Tool) -> &mut Self;
+}
+
+impl AddTool for App {
+    fn add_tool(&mut self, action: Action, tool: impl Tool) -> &mut Self {
+        self.world_mut()
+            .resource_mut::<ToolPalette>()
+            .tools
+            .push((action, Arc::new(tool)));
+        self
+    }
+}
+
+/// The tools the player can pick from, and the one picked
+#[derive(Resource, Default)]
+pub struct ToolPalette {
+    tools: Vec<(Action, Arc<dyn Tool>)>,
+    active: usize,
+}
+
+impl ToolPalette {
+    pub fn active(&self) -> Option<Arc<dyn Tool>> {
+        self.tools.get(self.active).map(|(_, tool)| tool.clone())
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index < self.tools.len() {
+            self.active = index;
+        }
+    }
+
+    /// Picks the next tool along the toolbar, or the previous one, wrapping around
+    pub fn cycle(&mut self, forward: bool) {
+        let count = self.tools.len();
+        if count > 0 {
+            let step = if forward { 1 } else { count - 1 };
+            self.active = (self.active + step) % count;
+        }
+    }
+}
+
+/// The last thing a tool reported, shown under the toolbar
+#[derive(Resource, Debug, Default)]
+pub struct ToolStatus(pub String);
+
+/// A drag with a mouse button held, from the click until the button is let go
+#[derive(Resource, Debug, Default)]
+struct Stroke {
+    active: bool,
+    inverse: bool,
+    /// cells the tool was already used on in this stroke
+    visited: HashSet<Entity>,
+    /// seconds held over the same cell since the tool was last used
+    repeat_time: f32,
+}
+
+#[derive(Component)]
+struct Toolbar;
+
+#[derive(Component)]
+struct ToolButton(usize);
+
+/// The text of a toolbar button, naming the key that picks the tool
+#[derive(Component)]
+struct ToolLabel(usize);
+
+#[derive(Component)]
+struct ToolStatusText;
+
+#[derive(Component)]
+struct BrushText;
+
+fn setup(mut commands: Commands, palette: Res<ToolPalette>) {
+    // Buttons to pick a tool, along the bottom of the screen
+    commands
+        .spawn((
+            Toolbar,
+            //  only the buttons should catch clicks meant for the map
+            PickingBehavior::IGNORE,
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(12.0),
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new(""), ToolStatusText, PickingBehavior::IGNORE));
+            parent.spawn((Text::new(""), BrushText, PickingBehavior::IGNORE));
+            parent
+                .spawn((
+                    PickingBehavior::IGNORE,
+                    Node {
+                        column_gap: Val::Px(4.0),
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    for index in 0..palette.tools.len() {
+                        parent
+                            .spawn((
+                                Button,
+                                ToolButton(index),
+                                BackgroundColor(TOOLBAR_COLOR),
+                                Node {
+                                    padding: UiRect::all(Val::Px(6.0)),
+                                    ..default()
+                                },
+                            ))
+                            .with_child((Text::new(""), ToolLabel(index)));
+                    }
+                });
+        });
+}
+
+/// The digit or letter of a key, or the key's name otherwise
+fn key_label(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Digit")
+        .or_else(|| name.strip_prefix("Key"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+/// The number keys pick a level instead while the level select is open
+fn tool_keys(
+    input: ActionInput,
+    level_select: Res<LevelSelectOpen>,
+    mut palette: ResMut<ToolPalette>,
+) {
+    //  nor on the frame a level is picked and it closes
+    if level_select.0 || level_select.is_changed() {
+        return;
+    }
+
+    let pressed = palette
+        .tools
+        .iter()
+        .position(|(action, _)| input.just_pressed(*action));
+    if let Some(index) = pressed {
+        palette.select(index);
+    }
+}
+
+fn toolbar_buttons(
+    buttons: Query<(&Interaction, &ToolButton), Changed<Interaction>>,
+    mut palette: ResMut<ToolPalette>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            palette.select(button.0);
+        }
+    }
+}
+
+/// Starts a stroke on the clicked cell
+fn start_stroke(
+    mut selection: EventReader<GroundSelected>,
+    palette: Res<ToolPalette>,
+    mut stroke: ResMut<Stroke>,
+    mut commands: Commands,
+) {
+    let Some(tool) = palette.active() else {
+        return;
+    };
+
+    for event in selection.read() {
+        *stroke = Stroke {
+            active: true,
+            inverse: event.inverse,
+            visited: HashSet::from([event.entity]),
+            repeat_time: 0.0,
+        };
+        //  the whole stroke is one step to undo
+        use_tool(
+            &mut commands,
+            tool.clone(),
+            event.entity,
+            stroke.inverse,
+            true,
+        );
+    }
+}
+
+/// Uses the tool once on each new cell dragged over, and again while held if the brush repeats
+fn continue_stroke(
+    input: ActionInput,
+    time: Res<Time>,
+    hovered: Res<HoveredGround>,
+    brush: Res<Brush>,
+    palette: Res<ToolPalette>,
+    mut stroke: ResMut<Stroke>,
+    mut commands: Commands,
+) {
+    if !stroke.active {
+        return;
+    }
+    if !input.pressed(Action::UseTool) && !input.pressed(Action::UseOpposite) {
+        stroke.active = false;
+        return;
+    }
+    let (Some(tool), Some(ground)) = (palette.active(), hovered.0) else {
+        return;
+    };
+    if !tool.drags() {
+        return;
+    }
+
+    if stroke.visited.insert(ground) {
+        stroke.repeat_time = 0.0;
+        use_tool(&mut commands, tool, ground, stroke.inverse, false);
+        return;
+    }
+
+    if brush.repeat <= 0.0 {
+        return;
+    }
+    let interval = 1.0 / brush.repeat;
+    stroke.repeat_time += time.delta_secs();
+    let mut uses = 0;
+    while stroke.repeat_time >= interval && uses < REPEAT_USES_MAX {
+        stroke.repeat_time -= interval;
+        uses += 1;
+        use_tool(&mut commands, tool.clone(), ground, stroke.inverse, false);
+    }
+    //  a slow frame catches up a little, the rest of the time is dropped rather than owed
+    stroke.repeat_time = stroke.repeat_time.min(interval);
+}
+
+/// Tools get the whole world, so each one reads and changes whatever it needs
+fn use_tool(
+    commands: &mut Commands,
+    tool: Arc<dyn Tool>,
+    center: Entity,
+    inverse: bool,
+    record: bool,
+) {
+    commands.queue(move |world: &mut World| {
+        let target = ToolTarget {
+            center,
+            cells: brush::footprint(world, center),
+            inverse,
+        };
+        if tool.edits(world) && !begin_edit(world, record) {
+            return;
+        }
+        tool.apply(world, &target);
+    });
+}
+
+/// Checks the budget before an edit is applied and spends one edit on it,
+/// however many cells it shifts, returning false if there are none left
+pub(crate) fn begin_edit(world: &mut World, record: bool) -> bool {
+    if !world.resource::<EditBudget>().can_edit() {
+        world.resource_mut::<ToolStatus>().0 = String::from("no edits left for this level");
+        return false;
+    }
+
+    //  the snapshot is taken first, so undoing also gives the edit back
+    if record {
+        undo::record_edit(world);
+    }
+    world.resource_mut::<EditBudget>().edits += 1;
+    true
+}
+
+fn update_toolbar(
+    mode: Res<State<GameMode>>,
+    palette: Res<ToolPalette>,
+    status: Res<ToolStatus>,
+    mut toolbar: Query<&mut Visibility, With<Toolbar>>,
+    mut buttons: Query<(&ToolButton, &mut BackgroundColor)>,
+    mut status_text: Query<&mut Text, With<ToolStatusText>>,
+) {
+    if !(mode.is_changed() || palette.is_changed() || status.is_changed()) {
+        return;
+    }
+
+    //  the editor has its own tools
+    for mut visibility in toolbar.iter_mut() {
+        *visibility = match mode.get() {
+            GameMode::Play => Visibility::Inherited,
+            GameMode::Edit => Visibility::Hidden,
+        };
+    }
+
+    for (button, mut color) in buttons.iter_mut() {
+        color.0 = if button.0 == palette.active {
+            ACTIVE_TOOL_COLOR
+        } else {
+            TOOLBAR_COLOR
+        };
+    }
+
+    if let Ok(mut text) = status_text.get_single_mut() {
+        **text = status.0.clone();
+    }
+}
+
+fn update_tool_labels(
+    palette: Res<ToolPalette>,
+    bindings: Res<Bindings>,
+    mut labels: Query<(&mut Text, &ToolLabel)>,
+) {
+    for (mut text, label) in labels.iter_mut() {
+        let Some((action, tool)) = palette.tools.get(label.0) else {
+            continue;
+        };
+        **text = match bindings.key(*action) {
+            Some(key) => format!("{} {}", key_label(key), tool.name()),
+            None => tool.name().to_string(),
+        };
+    }
+}
+
+fn update_brush_display(brush: Res<Brush>, mut query: Query<&mut Text, With<BrushText>>) {
+    if !brush.is_changed() {
+        return;
+    }
+
+    if let Ok(mut text) = query.get_single_mut() {
+        **text = brush.describe();
+    }
+}
@@ -0,0 +1,494 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    f32::consts::FRAC_PI_2,
+};
+
+use bevy::prelude::*;
+
+use crate::{
+    bindings::{Action, ActionInput},
+    editor::GameMode,
+    grid::GridCell,
+    ground::Ground,
+    selection::HoveredGround,
+    shifting::ShiftCell,
+    tuning::Tuning,
+};
+
+use super::{Tool, ToolPalette, ToolStatus, ToolTarget};
+
+const PREVIEW_COLOR: Color = Color::srgb(1.0, 0.9, 0.2);
+
+/// Each layer climbed or dropped costs as much as this many flat steps
+const CLIMB_COST: u32 = 3;
+
+pub struct PathPlugin;
+
+impl Plugin for PathPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PathSettings::default())
+            .insert_resource(PathStart::default())
+            .insert_resource(PathPreview::default());
+
+        app.add_systems(
+            Update,
+            (cancel_path, update_path_preview, draw_path_preview)
+                .chain()
+                .run_if(in_state(GameMode::Play)),
+        );
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PathRoute {
+    /// the cells nearest the line between the ends
+    #[default]
+    Straight,
+    /// the way with the least climbing
+    LeastCost,
+}
+
+impl PathRoute {
+    pub const ALL: [PathRoute; 2] = [PathRoute::Straight, PathRoute::LeastCost];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PathRoute::Straight => "straight",
+            PathRoute::LeastCost => "least-cost",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|route| route.name() == name)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PathProfile {
+    /// every cell moves by the depth, following the ground
+    #[default]
+    Depth,
+    /// cells form an even slope between the ends, offset by the depth
+    Gradient,
+}
+
+impl PathProfile {
+    pub const ALL: [PathProfile; 2] = [PathProfile::Depth, PathProfile::Gradient];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PathProfile::Depth => "depth",
+            PathProfile::Gradient => "gradient",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|profile| profile.name() == name)
+    }
+}
+
+/// How the path tool picks and shapes its cells
+#[derive(Resource, Debug, Clone)]
+pub struct PathSettings {
+    pub route: PathRoute,
+    pub profile: PathProfile,
+    /// layers to dig the channel, or to build the levee
+    pub depth: i32,
+}
+
+impl Default for PathSettings {
+    fn default() -> Self {
+        Self {
+            route: PathRoute::default(),
+            profile: PathProfile::default(),
+            depth: 1,
+        }
+    }
+}
+
+impl PathSettings {
+    pub fn describe(&self) -> String {
+        format!(
+            "{} path, {} {}",
+            self.route.name(),
+            self.profile.name(),
+            self.depth
+        )
+    }
+}
+
+/// The first cell of a path waiting for its end
+#[derive(Resource, Debug, Default)]
+struct PathStart(Option<Entity>);
+
+/// The cells of the previewed path, only searched again when it could have changed
+#[derive(Resource, Debug, Default)]
+struct PathPreview(Vec<Entity>);
+
+/// Digs a channel between two clicked cells, or builds a levee with the right button
+pub struct PathTool;
+
+impl Tool for PathTool {
+    fn name(&self) -> &'static str {
+        "path"
+    }
+
+    fn edits(&self, world: &World) -> bool {
+        //  only the second click changes the map
+        world.resource::<PathStart>().0.is_some()
+    }
+
+    fn drags(&self) -> bool {
+        false
+    }
+
+    fn apply(&self, world: &mut World, target: &ToolTarget) {
+        let Some(start) = world.resource_mut::<PathStart>().0.take() else {
+            world.resource_mut::<PathStart>().0 = Some(target.center);
+            let status = match world.get::<GridCell>(target.center) {
+                Some(cell) => format!(
+                    "{} from {}, {}: click the end, Esc to cancel",
+                    world.resource::<PathSettings>().describe(),
+                    cell.row,
+                    cell.col
+                ),
+                None => String::new(),
+            };
+            world.resource_mut::<ToolStatus>().0 = status;
+            return;
+        };
+
+        let cell_height = world.resource::<Tuning>().cell_height;
+        let settings = world.resource::<PathSettings>().clone();
+        let cells: HashMap<IVec2, (Entity, i32)> = world
+            .query_filtered::<(Entity, &GridCell), With<Ground>>()
+            .iter(world)
+            .map(|(entity, cell)| (cell.coordinates(), (entity, cell.layer_index(cell_height))))
+            .collect();
+        let (Some(from), Some(to)) = (
+            world.get::<GridCell>(start).map(|cell| cell.coordinates()),
+            world
+                .get::<GridCell>(target.center)
+                .map(|cell| cell.coordinates()),
+        ) else {
+            return;
+        };
+
+        let path = find_path(&cells, from, to, settings.route);
+        let depth = if target.inverse {
+            settings.depth
+        } else {
+            -settings.depth
+        };
+        for (coordinates, layer) in path_layers(&cells, &path, settings.profile, depth) {
+            let (entity, current) = cells[&coordinates];
+            if layer != current {
+                world.send_event(ShiftCell {
+                    entity,
+                    layers: layer - current,
+                    cascade: true,
+                });
+            }
+        }
+
+        world.resource_mut::<ToolStatus>().0 = format!("path of {} cells", path.len());
+    }
+}
+
+/// The cells from one end to the other, both included
+pub fn find_path(
+    cells: &HashMap<IVec2, (Entity, i32)>,
+    from: IVec2,
+    to: IVec2,
+    route: PathRoute,
+) -> Vec<IVec2> {
+    match route {
+        PathRoute::Straight => straight_path(from, to),
+        PathRoute::LeastCost => least_cost_path(cells, from, to).unwrap_or_default(),
+    }
+}
+
+/// Steps between side-by-side cells only, since water cannot flow across corners
+fn straight_path(from: IVec2, to: IVec2) -> Vec<IVec2> {
+    let delta = to - from;
+    let (steps_x, steps_y) = (delta.x.abs(), delta.y.abs());
+    let step = delta.signum();
+
+    let mut path = vec![from];
+    let mut current = from;
+    let (mut taken_x, mut taken_y) = (0, 0);
+    while taken_x < steps_x || taken_y < steps_y {
+        //  step along whichever axis has fallen behind the line
+        let progress_x = (taken_x as f32 + 0.5) / steps_x as f32;
+        let progress_y = (taken_y as f32 + 0.5) / steps_y as f32;
+        if progress_x < progress_y {
+            current.x += step.x;
+            taken_x += 1;
+        } else {
+            current.y += step.y;
+            taken_y += 1;
+        }
+        path.push(current);
+    }
+    path
+}
+
+/// Dijkstra's search, where climbing or dropping a layer costs more than a flat step
+fn least_cost_path(
+    cells: &HashMap<IVec2, (Entity, i32)>,
+    from: IVec2,
+    to: IVec2,
+) -> Option<Vec<IVec2>> {
+    let mut costs: HashMap<IVec2, u32> = HashMap::from([(from, 0)]);
+    let mut previous: HashMap<IVec2, IVec2> = HashMap::new();
+    let mut frontier = BinaryHeap::from([Reverse((0, from.x, from.y))]);
+
+    while let Some(Reverse((cost, x, y))) = frontier.pop() {
+        let current = IVec2::new(x, y);
+        if current == to {
+            break;
+        }
+        if costs.get(&current).is_some_and(|best| cost > *best) {
+            continue;
+        }
+
+        let (_, layer) = cells.get(&current)?;
+        for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+            let next = current + offset;
+            let Some((_, next_layer)) = cells.get(&next) else {
+                continue;
+            };
+
+            let next_cost = cost + 1 + CLIMB_COST * layer.abs_diff(*next_layer);
+            if costs.get(&next).is_none_or(|best| next_cost < *best) {
+                costs.insert(next, next_cost);
+                previous.insert(next, current);
+                frontier.push(Reverse((next_cost, next.x, next.y)));
+            }
+        }
+    }
+
+    let mut path = vec![to];
+    while let Some(step) = previous.get(path.last()?) {
+        path.push(*step);
+    }
+    path.reverse();
+    (path[0] == from).then_some(path)
+}
+
+/// The layer each cell of the path should end at
+fn path_layers(
+    cells: &HashMap<IVec2, (Entity, i32)>,
+    path: &[IVec2],
+    profile: PathProfile,
+    depth: i32,
+) -> Vec<(IVec2, i32)> {
+    let layer = |cell: &IVec2| cells.get(cell).map(|(_, layer)| *layer).unwrap_or(0);
+    let (Some(first), Some(last)) = (path.first(), path.last()) else {
+        return Vec::new();
+    };
+    let (start, end) = (layer(first) as f32, layer(last) as f32);
+    let length = (path.len() - 1).max(1) as f32;
+
+    path.iter()
+        .enumerate()
+        .map(|(index, cell)| {
+            let base = match profile {
+                PathProfile::Depth => layer(cell),
+                PathProfile::Gradient => start.lerp(end, index as f32 / length).round() as i32,
+            };
+            (*cell, base + depth)
+        })
+        .collect()
+}
+
+fn cancel_path(
+    input: ActionInput,
+    palette: Res<ToolPalette>,
+    mut start: ResMut<PathStart>,
+    mut status: ResMut<ToolStatus>,
+) {
+    //  picking another tool also drops the path
+    if start.0.is_some() && (input.just_pressed(Action::Cancel) || palette.is_changed()) {
+        start.0 = None;
+        status.0 = String::new();
+    }
+}
+
+/// Finds the path to the hovered cell again when either end, the settings or a layer changes
+fn update_path_preview(
+    start: Res<PathStart>,
+    hovered: Res<HoveredGround>,
+    settings: Res<PathSettings>,
+    tuning: Res<Tuning>,
+    grounds: Query<(Entity, &GridCell), With<Ground>>,
+    shifted: Query<(), (With<Ground>, Changed<GridCell>)>,
+    mut preview: ResMut<PathPreview>,
+) {
+    let Some(from) = start.0 else {
+        if !preview.0.is_empty() {
+            preview.0.clear();
+        }
+        return;
+    };
+    let changed = start.is_changed() || hovered.is_changed() || settings.is_changed();
+    if !changed && shifted.is_empty() {
+        return;
+    }
+    let to = hovered.0.unwrap_or(from);
+
+    let cells: HashMap<IVec2, (Entity, i32)> = grounds
+        .iter()
+        .map(|(entity, cell)| {
+            (
+                cell.coordinates(),
+                (entity, cell.layer_index(tuning.cell_height)),
+            )
+        })
+        .collect();
+    let (Ok((_, from)), Ok((_, to))) = (grounds.get(from), grounds.get(to)) else {
+        preview.0.clear();
+        return;
+    };
+
+    let path = find_path(&cells, from.coordinates(), to.coordinates(), settings.route);
+    preview.0 = path
+        .iter()
+        .filter_map(|cell| cells.get(cell))
+        .map(|(entity, _)| *entity)
+        .collect();
+}
+
+/// Shows the path to the hovered cell before the second click
+fn draw_path_preview(
+    mut gizmos: Gizmos,
+    preview: Res<PathPreview>,
+    grounds: Query<&GlobalTransform, With<Ground>>,
+) {
+    //  half a cube up, on the top faces
+    let tops: Vec<Vec3> = preview
+        .0
+        .iter()
+        .filter_map(|entity| grounds.get(*entity).ok())
+        .map(|transform| transform.translation() + Vec3::Y * 0.5)
+        .collect();
+    gizmos.linestrip(tops.iter().copied(), PREVIEW_COLOR);
+    for top in tops {
+        gizmos.rect(
+            Isometry3d::new(top, Quat::from_rotation_x(FRAC_PI_2)),
+            Vec2::splat(0.8),
+            PREVIEW_COLOR,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cells by row and column, with the given layers
+    fn grid(layers: &[&[i32]]) -> HashMap<IVec2, (Entity, i32)> {
+        let mut cells = HashMap::new();
+        for (row, columns) in layers.iter().enumerate() {
+            for (col, layer) in columns.iter().enumerate() {
+                cells.insert(
+                    IVec2::new(row as i32, col as i32),
+                    (Entity::PLACEHOLDER, *layer),
+                );
+            }
+        }
+        cells
+    }
+
+    fn side_by_side(path: &[IVec2]) -> bool {
+        path.windows(2)
+            .all(|pair| (pair[1] - pair[0]).abs().element_sum() == 1)
+    }
+
+    #[test]
+    fn diagonal_path_steps_side_by_side() {
+        let (from, to) = (IVec2::new(0, 0), IVec2::new(3, -5));
+        let path = straight_path(from, to);
+
+        assert_eq!(path.first(), Some(&from));
+        assert_eq!(path.last(), Some(&to));
+        assert_eq!(path.len(), 3 + 5 + 1);
+        assert!(side_by_side(&path));
+    }
+
+    #[test]
+    fn straight_path_follows_a_single_axis() {
+        let path = straight_path(IVec2::new(2, 0), IVec2::new(2, -3));
+        assert_eq!(
+            path,
+            vec![
+                IVec2::new(2, 0),
+                IVec2::new(2, -1),
+                IVec2::new(2, -2),
+                IVec2::new(2, -3)
+            ]
+        );
+    }
+
+    #[test]
+    fn path_to_the_same_cell_is_that_cell() {
+        let cells = grid(&[&[0]]);
+        let cell = IVec2::ZERO;
+
+        assert_eq!(straight_path(cell, cell), vec![cell]);
+        assert_eq!(least_cost_path(&cells, cell, cell), Some(vec![cell]));
+    }
+
+    #[test]
+    fn least_cost_path_goes_around_a_ridge() {
+        //  a wall down the middle column, open only on the last row
+        let cells = grid(&[&[0, 5, 0], &[0, 5, 0], &[0, 5, 0], &[0, 0, 0]]);
+        let (from, to) = (IVec2::new(0, 0), IVec2::new(0, 2));
+        let path = least_cost_path(&cells, from, to).unwrap();
+
+        assert_eq!(path.first(), Some(&from));
+        assert_eq!(path.last(), Some(&to));
+        assert!(side_by_side(&path));
+        assert!(path.iter().all(|cell| cells[cell].1 == 0));
+        assert!(path.contains(&IVec2::new(3, 1)));
+    }
+
+    #[test]
+    fn unreachable_cell_has_no_least_cost_path() {
+        let cells = grid(&[&[0, 0], &[0, 0]]);
+        let outside = IVec2::new(5, 5);
+
+        assert_eq!(least_cost_path(&cells, IVec2::ZERO, outside), None);
+        assert!(find_path(&cells, IVec2::ZERO, outside, PathRoute::LeastCost).is_empty());
+    }
+
+    #[test]
+    fn gradient_hits_both_ends() {
+        let cells = grid(&[&[0, 3, 3, 0, 6]]);
+        let path: Vec<IVec2> = (0..5).map(|col| IVec2::new(0, col)).collect();
+        let layers: Vec<i32> = path_layers(&cells, &path, PathProfile::Gradient, -1)
+            .into_iter()
+            .map(|(_, layer)| layer)
+            .collect();
+
+        //  0 to 6 over four steps rounds to an even slope, dug one layer down
+        assert_eq!(layers, vec![-1, 1, 2, 4, 5]);
+    }
+
+    #[test]
+    fn depth_follows_the_ground() {
+        let cells = grid(&[&[0, 3, 1]]);
+        let path: Vec<IVec2> = (0..3).map(|col| IVec2::new(0, col)).collect();
+        let layers: Vec<(IVec2, i32)> = path_layers(&cells, &path, PathProfile::Depth, 2);
+
+        assert_eq!(
+            layers,
+            vec![
+                (IVec2::new(0, 0), 2),
+                (IVec2::new(0, 1), 5),
+                (IVec2::new(0, 2), 3)
+            ]
+        );
+    }
+}
@@ -0,0 +1,424 @@
+use std::{collections::HashMap, f32::consts::FRAC_PI_2};
+
+use bevy::prelude::*;
+
+use crate::{
+    bindings::{Action, ActionInput},
+    editor::GameMode,
+    grid::GridCell,
+    ground::Ground,
+    pair::Pair,
+    selection::{HoveredGround, SelectedCells},
+    shifting::{Locked, ShiftCell},
+    tuning::Tuning,
+    water::{ManuallyIncreaseWater, Water},
+};
+
+use super::{begin_edit, brush::Brush, Tool, ToolStatus, ToolTarget};
+
+const SELECTED_COLOR: Color = Color::srgb(0.2, 1.0, 0.4);
+const LOCKED_COLOR: Color = Color::srgb(1.0, 0.3, 0.2);
+const BOX_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+
+/// half a cube, from a cell's center to its top face
+const CELL_TOP: f32 = 0.5;
+
+pub struct SelectPlugin;
+
+impl Plugin for SelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BoxSelect::default())
+            .insert_resource(HeldSelectMode::default());
+
+        app.add_systems(
+            Update,
+            (
+                track_select_mode,
+                (track_box_select, finish_box_select).chain(),
+                selection_keys,
+                draw_box_select,
+                draw_selection_outline,
+            )
+                .run_if(in_state(GameMode::Play)),
+        );
+    }
+}
+
+/// How a box changes the cells already selected
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SelectMode {
+    /// the box becomes the selection
+    #[default]
+    Replace,
+    /// the box is added, with the select-add action held, Shift or LT
+    Add,
+    /// each cell in the box flips, with the select-toggle action held, Ctrl or RT
+    Toggle,
+    /// the box is taken out, with the right button
+    Remove,
+}
+
+/// A box being dragged with the select tool
+#[derive(Resource, Debug, Default)]
+struct BoxSelect {
+    anchor: Option<Entity>,
+    end: Option<Entity>,
+    mode: SelectMode,
+}
+
+/// The mode a box starts in, from the actions held, so the tool reads them through the bindings
+#[derive(Resource, Debug, Default)]
+struct HeldSelectMode(SelectMode);
+
+/// Selects the cells in a dragged box, for changing them all at once
+pub struct SelectTool;
+
+impl Tool for SelectTool {
+    fn name(&self) -> &'static str {
+        "select"
+    }
+
+    fn edits(&self, _world: &World) -> bool {
+        false
+    }
+
+    fn drags(&self) -> bool {
+        false
+    }
+
+    fn apply(&self, world: &mut World, target: &ToolTarget) {
+        let mode = if target.inverse {
+            SelectMode::Remove
+        } else {
+            world.resource::<HeldSelectMode>().0
+        };
+
+        *world.resource_mut::<BoxSelect>() = BoxSelect {
+            anchor: Some(target.center),
+            end: Some(target.center),
+            mode,
+        };
+    }
+}
+
+/// Changes the selection by a set of cells
+pub fn select_cells(
+    selected: &mut SelectedCells,
+    cells: impl IntoIterator<Item = Entity>,
+    mode: SelectMode,
+) {
+    if mode == SelectMode::Replace {
+        selected.0.clear();
+    }
+
+    for cell in cells {
+        match mode {
+            SelectMode::Replace | SelectMode::Add => {
+                selected.0.insert(cell);
+            }
+            SelectMode::Toggle => {
+                if !selected.0.remove(&cell) {
+                    selected.0.insert(cell);
+                }
+            }
+            SelectMode::Remove => {
+                selected.0.remove(&cell);
+            }
+        }
+    }
+}
+
+/// The selected cells with their layers
+fn selected_layers(world: &World) -> Vec<(Entity, i32)> {
+    let cell_height = world.resource::<Tuning>().cell_height;
+    world
+        .resource::<SelectedCells>()
+        .0
+        .iter()
+        .filter_map(|ground| {
+            let cell = world.get::<GridCell>(*ground)?;
+            Some((*ground, cell.layer_index(cell_height)))
+        })
+        .collect()
+}
+
+/// Shifts every selected cell by the same number of layers, returning how many were sent
+pub fn shift_selection(world: &mut World, layers: i32, cascade: bool) -> usize {
+    let cells = selected_layers(world);
+    for (ground, _) in cells.iter() {
+        world.send_event(ShiftCell {
+            entity: *ground,
+            layers,
+            cascade,
+        });
+    }
+    cells.len()
+}
+
+/// The mean layer of the selected cells, rounded
+fn average_layer(world: &World) -> Option<i32> {
+    let cells = selected_layers(world);
+    if cells.is_empty() {
+        return None;
+    }
+    let total: i32 = cells.iter().map(|(_, layer)| layer).sum();
+    Some((total as f32 / cells.len() as f32).round() as i32)
+}
+
+/// Shifts every selected cell to the same layer, returning how many moved
+pub fn set_selection_layer(world: &mut World, layer: i32, cascade: bool) -> usize {
+    let cells: Vec<(Entity, i32)> = selected_layers(world)
+        .into_iter()
+        .filter(|(_, current)| *current != layer)
+        .collect();
+    for (ground, current) in cells.iter() {
+        world.send_event(ShiftCell {
+            entity: *ground,
+            layers: layer - current,
+            cascade,
+        });
+    }
+    cells.len()
+}
+
+/// Pours water on the selected cells until the surface reaches the top of a layer
+///
+/// Without a layer, the highest selected cell is used, filling the selection to its brim.
+pub fn fill_selection(world: &mut World, level: Option<i32>) -> usize {
+    let cells = selected_layers(world);
+    let Some(level) = level.or_else(|| cells.iter().map(|(_, layer)| *layer).max()) else {
+        return 0;
+    };
+    let surface = level as f32 * world.resource::<Tuning>().cell_height;
+
+    let pairs: Vec<Pair> = world.query::<&Pair>().iter(world).cloned().collect();
+    let waters: HashMap<Entity, f32> = pairs
+        .iter()
+        .filter_map(|pair| Some((pair.ground, world.get::<Water>(pair.water)?.amount)))
+        .collect();
+
+    let mut filled = 0;
+    for (ground, _) in cells {
+        let Some(cell) = world.get::<GridCell>(ground) else {
+            continue;
+        };
+        let amount = surface - cell.layer - waters.get(&ground).copied().unwrap_or(0.0);
+        if amount > 0.0 {
+            world.send_event(ManuallyIncreaseWater { ground, amount });
+            filled += 1;
+        }
+    }
+    filled
+}
+
+/// Holds the selected cells in place, or lets them go, returning how many changed
+pub fn lock_selection(world: &mut World, lock: bool) -> usize {
+    let cells: Vec<Entity> = world
+        .resource::<SelectedCells>()
+        .0
+        .iter()
+        .copied()
+        .filter(|ground| world.get::<Locked>(*ground).is_some() != lock)
+        .collect();
+
+    for ground in cells.iter() {
+        if lock {
+            world.entity_mut(*ground).insert(Locked);
+        } else {
+            world.entity_mut(*ground).remove::<Locked>();
+        }
+    }
+    cells.len()
+}
+
+/// The ground cells in the rectangle between two cells, both included
+fn box_cells(
+    grounds: &Query<(Entity, &GridCell, &GlobalTransform), With<Ground>>,
+    from: Entity,
+    to: Entity,
+) -> Vec<Entity> {
+    let (Ok((_, from, _)), Ok((_, to, _))) = (grounds.get(from), grounds.get(to)) else {
+        return Vec::new();
+    };
+    let (min, max) = (
+        from.coordinates().min(to.coordinates()),
+        from.coordinates().max(to.coordinates()),
+    );
+
+    grounds
+        .iter()
+        .filter(|(_, cell, _)| {
+            let coordinates = cell.coordinates();
+            coordinates.cmpge(min).all() && coordinates.cmple(max).all()
+        })
+        .map(|(entity, _, _)| entity)
+        .collect()
+}
+
+fn track_select_mode(input: ActionInput, mut held: ResMut<HeldSelectMode>) {
+    let mode = if input.pressed(Action::SelectAdd) {
+        SelectMode::Add
+    } else if input.pressed(Action::SelectToggle) {
+        SelectMode::Toggle
+    } else {
+        SelectMode::Replace
+    };
+    if held.0 != mode {
+        held.0 = mode;
+    }
+}
+
+/// Follows the pointer while the box is dragged, keeping the last cell when it leaves the map
+fn track_box_select(hovered: Res<HoveredGround>, mut box_select: ResMut<BoxSelect>) {
+    if box_select.anchor.is_some() && hovered.0.is_some() && box_select.end != hovered.0 {
+        box_select.end = hovered.0;
+    }
+}
+
+fn finish_box_select(
+    input: ActionInput,
+    mut box_select: ResMut<BoxSelect>,
+    grounds: Query<(Entity, &GridCell, &GlobalTransform), With<Ground>>,
+    mut selected: ResMut<SelectedCells>,
+    mut status: ResMut<ToolStatus>,
+) {
+    let Some(anchor) = box_select.anchor else {
+        return;
+    };
+    if input.pressed(Action::UseTool) || input.pressed(Action::UseOpposite) {
+        return;
+    }
+
+    let cells = box_cells(&grounds, anchor, box_select.end.unwrap_or(anchor));
+    select_cells(&mut selected, cells, box_select.mode);
+    *box_select = BoxSelect::default();
+    status.0 = format!("{} cells selected", selected.0.len());
+}
+
+/// Changes every selected cell at once
+fn selection_keys(
+    input: ActionInput,
+    brush: Res<Brush>,
+    mut selected: ResMut<SelectedCells>,
+    mut commands: Commands,
+) {
+    if selected.0.is_empty() {
+        return;
+    }
+
+    let (strength, target) = (brush.strength, brush.target);
+    if input.just_pressed(Action::Cancel) {
+        selected.0.clear();
+    } else if input.just_pressed(Action::RaiseSelection) {
+        edit_selection(&mut commands, move |world| {
+            format!("raised {} cells", shift_selection(world, strength, true))
+        });
+    } else if input.just_pressed(Action::LowerSelection) {
+        edit_selection(&mut commands, move |world| {
+            format!("lowered {} cells", shift_selection(world, -strength, true))
+        });
+    } else if input.just_pressed(Action::LevelSelection) {
+        edit_selection(&mut commands, move |world| {
+            //  the brush's flatten target, or the selection's average
+            let Some(layer) = target.or_else(|| average_layer(world)) else {
+                return String::new();
+            };
+            let moved = set_selection_layer(world, layer, true);
+            format!("leveled {} cells to layer {}", moved, layer)
+        });
+    } else if input.just_pressed(Action::FillSelection) {
+        edit_selection(&mut commands, |world| {
+            format!("filled {} cells", fill_selection(world, None))
+        });
+    } else if input.just_pressed(Action::LockSelection) {
+        //  locks are not edits of the map, so there is nothing to undo
+        commands.queue(|world: &mut World| {
+            //  lock them all, unless all are locked already
+            let locked = lock_selection(world, true);
+            let status = if locked > 0 {
+                format!("locked {} cells", locked)
+            } else {
+                format!("unlocked {} cells", lock_selection(world, false))
+            };
+            world.resource_mut::<ToolStatus>().0 = status;
+        });
+    }
+}
+
+/// Runs a change of the selection as one edit and one step to undo, showing what it did
+fn edit_selection(
+    commands: &mut Commands,
+    edit: impl FnOnce(&mut World) -> String + Send + 'static,
+) {
+    commands.queue(move |world: &mut World| {
+        if !begin_edit(world, true) {
+            return;
+        }
+        let status = edit(world);
+        world.resource_mut::<ToolStatus>().0 = status;
+    });
+}
+
+/// Shows the box being dragged
+fn draw_box_select(
+    mut gizmos: Gizmos,
+    box_select: Res<BoxSelect>,
+    grounds: Query<(Entity, &GridCell, &GlobalTransform), With<Ground>>,
+) {
+    let Some(anchor) = box_select.anchor else {
+        return;
+    };
+
+    for ground in box_cells(&grounds, anchor, box_select.end.unwrap_or(anchor)) {
+        let Ok((_, _, transform)) = grounds.get(ground) else {
+            continue;
+        };
+        gizmos.rect(
+            Isometry3d::new(
+                transform.translation() + Vec3::Y * CELL_TOP,
+                Quat::from_rotation_x(FRAC_PI_2),
+            ),
+            Vec2::splat(0.8),
+            BOX_COLOR,
+        );
+    }
+}
+
+/// Outlines the edges of the selection, in red around locked cells
+fn draw_selection_outline(
+    mut gizmos: Gizmos,
+    selected: Res<SelectedCells>,
+    grounds: Query<(&GridCell, &GlobalTransform, Option<&Locked>), With<Ground>>,
+) {
+    let cells: HashMap<IVec2, (Vec3, bool)> = selected
+        .0
+        .iter()
+        .filter_map(|ground| grounds.get(*ground).ok())
+        .map(|(cell, transform, locked)| {
+            (
+                cell.coordinates(),
+                (transform.translation(), locked.is_some()),
+            )
+        })
+        .collect();
+
+    for (coordinates, (translation, locked)) in cells.iter() {
+        let color = if *locked {
+            LOCKED_COLOR
+        } else {
+            SELECTED_COLOR
+        };
+        let top = *translation + Vec3::Y * CELL_TOP;
+
+        //  only the sides facing away from the selection
+        for side in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+            if cells.contains_key(&(*coordinates + side)) {
+                continue;
+            }
+            //  rows run along x and columns along z
+            let middle = top + Vec3::new(side.x as f32, 0.0, side.y as f32) * 0.5;
+            let along = Vec3::new(side.y as f32, 0.0, side.x as f32) * 0.5;
+            gizmos.line(middle - along, middle + along, color);
+        }
+    }
+}
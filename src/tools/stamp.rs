@@ -0,0 +1,550 @@
+use std::{collections::HashMap, f32::consts::FRAC_PI_2};
+
+use bevy::prelude::*;
+
+use crate::{
+    bindings::{Action, ActionInput},
+    editor::GameMode,
+    grid::GridCell,
+    ground::Ground,
+    map::GenerateMap,
+    pair::Pair,
+    selection::HoveredGround,
+    shifting::{Locked, ShiftCell},
+    soil::Soil,
+    storage::{Stamp, StampCell},
+    tuning::Tuning,
+    water::{ManuallyIncreaseWater, Water},
+};
+
+use super::{Tool, ToolPalette, ToolStatus, ToolTarget};
+
+const COPY_COLOR: Color = Color::srgb(0.3, 0.8, 1.0);
+const PASTE_COLOR: Color = Color::srgb(1.0, 0.5, 0.9);
+
+pub struct StampPlugin;
+
+impl Plugin for StampPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Clipboard::default())
+            .insert_resource(PasteSettings::default())
+            .insert_resource(CopyCorner::default());
+
+        app.add_systems(
+            Update,
+            (
+                (
+                    cancel_copy,
+                    paste_keys,
+                    draw_copy_preview,
+                    draw_paste_preview,
+                )
+                    .run_if(in_state(GameMode::Play)),
+                clear_copy,
+            ),
+        );
+    }
+}
+
+/// The stamp the paste tool puts down
+#[derive(Resource, Debug, Default)]
+pub struct Clipboard(pub Option<Stamp>);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StampBlend {
+    /// the stamp's shape, soil and water, standing on the clicked cell
+    #[default]
+    Replace,
+    /// the stamp's layers and water on top of what is there
+    Add,
+    /// whichever of the stamp and the ground is higher, and the deeper water
+    Max,
+}
+
+impl StampBlend {
+    pub const ALL: [StampBlend; 3] = [StampBlend::Replace, StampBlend::Add, StampBlend::Max];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StampBlend::Replace => "replace",
+            StampBlend::Add => "add",
+            StampBlend::Max => "max",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|blend| blend.name() == name)
+    }
+
+    pub fn next(&self) -> StampBlend {
+        let index = Self::ALL
+            .iter()
+            .position(|blend| blend == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// How the clipboard is turned and blended when pasted
+#[derive(Resource, Debug, Default, Clone)]
+pub struct PasteSettings {
+    /// quarter turns counterclockwise, 0 to 3
+    pub turns: u8,
+    /// whether columns are flipped before turning
+    pub mirror: bool,
+    pub blend: StampBlend,
+}
+
+impl PasteSettings {
+    pub fn describe(&self) -> String {
+        let mut description = format!("{} paste, turned {}°", self.blend.name(), self.turns * 90);
+        if self.mirror {
+            description += ", mirrored";
+        }
+        description
+    }
+
+    /// Where a stamp cell lands, relative to the clicked cell
+    fn place(&self, offset: IVec2) -> IVec2 {
+        let mut offset = if self.mirror {
+            IVec2::new(offset.x, -offset.y)
+        } else {
+            offset
+        };
+        for _ in 0..self.turns {
+            offset = IVec2::new(-offset.y, offset.x);
+        }
+        offset
+    }
+}
+
+/// The first corner of a region waiting for the opposite one
+#[derive(Resource, Debug, Default)]
+struct CopyCorner(Option<Entity>);
+
+/// Copies the region between two clicked corners to the clipboard
+pub struct CopyTool;
+
+impl Tool for CopyTool {
+    fn name(&self) -> &'static str {
+        "copy"
+    }
+
+    fn edits(&self, _world: &World) -> bool {
+        false
+    }
+
+    fn drags(&self) -> bool {
+        false
+    }
+
+    fn apply(&self, world: &mut World, target: &ToolTarget) {
+        let Some(corner) = world.resource_mut::<CopyCorner>().0.take() else {
+            world.resource_mut::<CopyCorner>().0 = Some(target.center);
+            let status = match world.get::<GridCell>(target.center) {
+                Some(cell) => format!(
+                    "copying from {}, {}: click the opposite corner, Esc to cancel",
+                    cell.row, cell.col
+                ),
+                None => String::new(),
+            };
+            world.resource_mut::<ToolStatus>().0 = status;
+            return;
+        };
+
+        let (Some(from), Some(to)) = (
+            world.get::<GridCell>(corner).map(|cell| cell.coordinates()),
+            world
+                .get::<GridCell>(target.center)
+                .map(|cell| cell.coordinates()),
+        ) else {
+            return;
+        };
+
+        let stamp = copy_region(world, from, to);
+        world.resource_mut::<ToolStatus>().0 = format!("copied {} cells", stamp.cells.len());
+        world.resource_mut::<Clipboard>().0 = Some(stamp);
+    }
+}
+
+/// Puts the clipboard down centered on the clicked cell
+pub struct PasteTool;
+
+impl Tool for PasteTool {
+    fn name(&self) -> &'static str {
+        "paste"
+    }
+
+    fn edits(&self, world: &World) -> bool {
+        world.resource::<Clipboard>().0.is_some()
+    }
+
+    fn drags(&self) -> bool {
+        false
+    }
+
+    fn apply(&self, world: &mut World, target: &ToolTarget) {
+        let Some(stamp) = world.resource::<Clipboard>().0.clone() else {
+            world.resource_mut::<ToolStatus>().0 = String::from("nothing to paste, copy first");
+            return;
+        };
+
+        let settings = world.resource::<PasteSettings>().clone();
+        let pasted = paste(world, &stamp, target.center, &settings);
+        world.resource_mut::<ToolStatus>().0 =
+            format!("pasted {} cells, {}", pasted, settings.describe());
+    }
+}
+
+/// The ground cells by their coordinates, with their layer
+fn ground_layers(world: &mut World) -> HashMap<IVec2, (Entity, i32)> {
+    let cell_height = world.resource::<Tuning>().cell_height;
+    world
+        .query_filtered::<(Entity, &GridCell), With<Ground>>()
+        .iter(world)
+        .map(|(entity, cell)| (cell.coordinates(), (entity, cell.layer_index(cell_height))))
+        .collect()
+}
+
+/// The water height on each ground cell
+fn water_amounts(world: &mut World) -> HashMap<Entity, f32> {
+    let pairs: Vec<Pair> = world.query::<&Pair>().iter(world).cloned().collect();
+    pairs
+        .iter()
+        .filter_map(|pair| Some((pair.ground, world.get::<Water>(pair.water)?.amount)))
+        .collect()
+}
+
+/// Copies the rectangle between two corners, both included
+pub fn copy_region(world: &mut World, from: IVec2, to: IVec2) -> Stamp {
+    let (min, max) = (from.min(to), from.max(to));
+    let center = (min + max) / 2;
+    let cells = ground_layers(world);
+    let waters = water_amounts(world);
+    let base = cells.get(&center).map(|(_, layer)| *layer).unwrap_or(0);
+
+    let mut stamp = Stamp::default();
+    for row in min.x..=max.x {
+        for col in min.y..=max.y {
+            let coordinates = IVec2::new(row, col);
+            let Some((entity, layer)) = cells.get(&coordinates) else {
+                continue;
+            };
+            stamp.cells.push(StampCell {
+                offset: coordinates - center,
+                layer: layer - base,
+                soil: world.get::<Soil>(*entity).copied().unwrap_or_default(),
+                water: waters.get(entity).copied().unwrap_or(0.0),
+            });
+        }
+    }
+    stamp
+}
+
+/// Pastes a stamp centered on a ground cell, returning the number of cells it covered
+pub fn paste(world: &mut World, stamp: &Stamp, center: Entity, settings: &PasteSettings) -> usize {
+    let Some(origin) = world.get::<GridCell>(center).map(|cell| cell.coordinates()) else {
+        return 0;
+    };
+    let cells = ground_layers(world);
+    let waters = water_amounts(world);
+    let base = cells.get(&origin).map(|(_, layer)| *layer).unwrap_or(0);
+
+    let mut pasted = 0;
+    for stamp_cell in stamp.cells.iter() {
+        let Some((entity, current)) = cells.get(&(origin + settings.place(stamp_cell.offset)))
+        else {
+            continue;
+        };
+        //  locked cells keep their layer, soil and water alike
+        if world.get::<Locked>(*entity).is_some() {
+            continue;
+        }
+        let water = waters.get(entity).copied().unwrap_or(0.0);
+
+        let (layer, new_water) = match settings.blend {
+            StampBlend::Replace => (base + stamp_cell.layer, stamp_cell.water),
+            StampBlend::Add => (current + stamp_cell.layer, water + stamp_cell.water),
+            StampBlend::Max => (
+                (*current).max(base + stamp_cell.layer),
+                water.max(stamp_cell.water),
+            ),
+        };
+
+        //  the stamp is put down exactly, without pulling its neighbors
+        if layer != *current {
+            world.send_event(ShiftCell {
+                entity: *entity,
+                layers: layer - current,
+                cascade: false,
+            });
+        }
+        if new_water != water {
+            world.send_event(ManuallyIncreaseWater {
+                ground: *entity,
+                amount: new_water - water,
+            });
+        }
+        if settings.blend == StampBlend::Replace
+            && world.get::<Soil>(*entity) != Some(&stamp_cell.soil)
+        {
+            world.entity_mut(*entity).insert(stamp_cell.soil);
+        }
+        pasted += 1;
+    }
+    pasted
+}
+
+fn paste_keys(
+    input: ActionInput,
+    mut settings: ResMut<PasteSettings>,
+    mut status: ResMut<ToolStatus>,
+) {
+    let (turn, mirror, blend) = (
+        input.just_pressed(Action::TurnStamp),
+        input.just_pressed(Action::MirrorStamp),
+        input.just_pressed(Action::BlendStamp),
+    );
+    if !(turn || mirror || blend) {
+        return;
+    }
+
+    if turn {
+        settings.turns = (settings.turns + 1) % 4;
+    }
+    if mirror {
+        settings.mirror = !settings.mirror;
+    }
+    if blend {
+        settings.blend = settings.blend.next();
+    }
+    status.0 = settings.describe();
+}
+
+fn cancel_copy(
+    input: ActionInput,
+    palette: Res<ToolPalette>,
+    mut corner: ResMut<CopyCorner>,
+    mut status: ResMut<ToolStatus>,
+) {
+    //  picking another tool also drops the corner
+    if corner.0.is_some() && (input.just_pressed(Action::Cancel) || palette.is_changed()) {
+        corner.0 = None;
+        status.0 = String::new();
+    }
+}
+
+/// A corner on an old map is gone with it
+fn clear_copy(mut event: EventReader<GenerateMap>, mut corner: ResMut<CopyCorner>) {
+    if event.read().last().is_some() {
+        corner.0 = None;
+    }
+}
+
+/// Shows the region that the second click would copy
+fn draw_copy_preview(
+    gizmos: Gizmos,
+    corner: Res<CopyCorner>,
+    hovered: Res<HoveredGround>,
+    grounds: Query<(&GridCell, &GlobalTransform), With<Ground>>,
+) {
+    let Some(corner) = corner.0 else {
+        return;
+    };
+    let (Ok((from, _)), Ok((to, _))) = (
+        grounds.get(corner),
+        grounds.get(hovered.0.unwrap_or(corner)),
+    ) else {
+        return;
+    };
+
+    let (min, max) = (
+        from.coordinates().min(to.coordinates()),
+        from.coordinates().max(to.coordinates()),
+    );
+    let region = grounds.iter().filter(|(cell, _)| {
+        let coordinates = cell.coordinates();
+        coordinates.cmpge(min).all() && coordinates.cmple(max).all()
+    });
+    outline_cells(gizmos, region.map(|(_, transform)| transform), COPY_COLOR);
+}
+
+/// Shows where the clipboard would land on the hovered cell
+fn draw_paste_preview(
+    gizmos: Gizmos,
+    palette: Res<ToolPalette>,
+    clipboard: Res<Clipboard>,
+    settings: Res<PasteSettings>,
+    hovered: Res<HoveredGround>,
+    grounds: Query<(&GridCell, &GlobalTransform), With<Ground>>,
+) {
+    let Some(stamp) = &clipboard.0 else {
+        return;
+    };
+    if palette
+        .active()
+        .is_none_or(|tool| tool.name() != PasteTool.name())
+    {
+        return;
+    }
+    let Some(Ok((center, _))) = hovered.0.map(|ground| grounds.get(ground)) else {
+        return;
+    };
+
+    let origin = center.coordinates();
+    let footprint: Vec<IVec2> = stamp
+        .cells
+        .iter()
+        .map(|cell| origin + settings.place(cell.offset))
+        .collect();
+    let covered = grounds
+        .iter()
+        .filter(|(cell, _)| footprint.contains(&cell.coordinates()));
+    outline_cells(gizmos, covered.map(|(_, transform)| transform), PASTE_COLOR);
+}
+
+fn outline_cells<'a>(
+    mut gizmos: Gizmos,
+    cells: impl Iterator<Item = &'a GlobalTransform>,
+    color: Color,
+) {
+    for transform in cells {
+        //  half a cube up, on the top face
+        gizmos.rect(
+            Isometry3d::new(
+                transform.translation() + Vec3::Y * 0.5,
+                Quat::from_rotation_x(FRAC_PI_2),
+            ),
+            Vec2::splat(0.9),
+            color,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A world with ground cells at the given coordinates and layers
+    fn ground_world(layer: impl Fn(IVec2) -> i32, rows: i32, cols: i32) -> World {
+        let mut world = World::new();
+        world.init_resource::<Tuning>();
+        world.init_resource::<Events<ShiftCell>>();
+        world.init_resource::<Events<ManuallyIncreaseWater>>();
+        let cell_height = world.resource::<Tuning>().cell_height;
+        for row in -rows..=rows {
+            for col in -cols..=cols {
+                let coordinates = IVec2::new(row, col);
+                world.spawn((
+                    Ground,
+                    GridCell {
+                        row,
+                        col,
+                        layer: layer(coordinates) as f32 * cell_height,
+                    },
+                ));
+            }
+        }
+        world
+    }
+
+    fn ground_at(world: &mut World, coordinates: IVec2) -> Entity {
+        ground_layers(world)[&coordinates].0
+    }
+
+    #[test]
+    fn four_turns_are_the_identity() {
+        for mirror in [false, true] {
+            let settings = PasteSettings {
+                mirror,
+                ..default()
+            };
+            let turned = PasteSettings {
+                turns: 4,
+                mirror,
+                ..default()
+            };
+            for offset in [IVec2::new(1, 2), IVec2::new(-3, 0), IVec2::new(0, -1)] {
+                assert_eq!(turned.place(offset), settings.place(offset));
+            }
+        }
+    }
+
+    #[test]
+    fn turns_go_counterclockwise() {
+        let settings = PasteSettings {
+            turns: 1,
+            ..default()
+        };
+        assert_eq!(settings.place(IVec2::new(1, 2)), IVec2::new(-2, 1));
+        assert_eq!(settings.place(IVec2::new(-2, 1)), IVec2::new(-1, -2));
+    }
+
+    #[test]
+    fn mirror_flips_columns_before_turning() {
+        let mirrored = PasteSettings {
+            mirror: true,
+            ..default()
+        };
+        assert_eq!(mirrored.place(IVec2::new(1, 2)), IVec2::new(1, -2));
+
+        let settings = PasteSettings {
+            turns: 1,
+            mirror: true,
+            ..default()
+        };
+        //  (1, 2) mirrors to (1, -2), then turns to (2, 1)
+        assert_eq!(settings.place(IVec2::new(1, 2)), IVec2::new(2, 1));
+    }
+
+    #[test]
+    fn even_region_centers_toward_zero() {
+        let mut world = ground_world(|_| 0, 4, 4);
+        //  four rows from -3 to 0 and four columns from -1 to 2
+        let stamp = copy_region(&mut world, IVec2::new(0, 2), IVec2::new(-3, -1));
+
+        assert_eq!(stamp.cells.len(), 16);
+        //  (-3 + 0) / 2 rounds toward zero, to -1 rather than -2
+        let offsets: Vec<IVec2> = stamp.cells.iter().map(|cell| cell.offset).collect();
+        assert!(offsets.contains(&IVec2::ZERO));
+        assert_eq!(
+            offsets.iter().copied().reduce(IVec2::min),
+            Some(IVec2::new(-2, -1))
+        );
+        assert_eq!(
+            offsets.iter().copied().reduce(IVec2::max),
+            Some(IVec2::new(1, 2))
+        );
+    }
+
+    #[test]
+    fn paste_keeps_layers_relative_to_the_center() {
+        //  a slope in the top rows and flat ground at layer 4 in the bottom ones
+        let slope = |cell: IVec2| cell.x + 2 * cell.y;
+        let mut world = ground_world(|cell| if cell.x < 0 { slope(cell) } else { 4 }, 4, 1);
+        let stamp = copy_region(&mut world, IVec2::new(-3, -1), IVec2::new(-1, 1));
+        let center = ground_at(&mut world, IVec2::new(2, 0));
+
+        let pasted = paste(&mut world, &stamp, center, &PasteSettings::default());
+        assert_eq!(pasted, 9);
+
+        let mut layers: HashMap<IVec2, i32> = HashMap::new();
+        let shifts: Vec<ShiftCell> = world.resource_mut::<Events<ShiftCell>>().drain().collect();
+        for shift in shifts {
+            assert!(!shift.cascade);
+            let coordinates = world.get::<GridCell>(shift.entity).unwrap().coordinates();
+            layers.insert(coordinates, 4 + shift.layers);
+        }
+        for row in -1..=1 {
+            for col in -1..=1 {
+                let offset = IVec2::new(row, col);
+                let layer = layers
+                    .get(&(IVec2::new(2, 0) + offset))
+                    .copied()
+                    .unwrap_or(4);
+                let source = IVec2::new(-2, 0) + offset;
+                assert_eq!(layer - 4, slope(source) - slope(IVec2::new(-2, 0)));
+            }
+        }
+    }
+}
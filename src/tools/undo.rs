@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+
+use crate::{
+    bindings::{Action, ActionInput},
+    editor::GameMode,
+    grid::GridCell,
+    ground::Ground,
+    map::GenerateMap,
+    pair::Pair,
+    scoring::EditBudget,
+    shifting::Shifting,
+    soil::Soil,
+    water::Water,
+};
+
+use super::ToolStatus;
+
+const UNDO_LIMIT: usize = 50;
+
+pub struct UndoPlugin;
+
+impl Plugin for UndoPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(UndoHistory::default());
+
+        app.add_systems(
+            Update,
+            (clear_history, undo_key.run_if(in_state(GameMode::Play))),
+        );
+    }
+}
+
+/// The map as it was before each of the player's recent edits
+#[derive(Resource, Debug, Default)]
+pub struct UndoHistory {
+    steps: Vec<Snapshot>,
+}
+
+#[derive(Debug)]
+struct Snapshot {
+    grounds: Vec<(Entity, f32, Soil)>,
+    /// each water entity with its amount and the layer of its ground
+    waters: Vec<(Entity, f32, f32)>,
+    edits: u32,
+    layer_changes: u32,
+}
+
+/// Remembers the whole map, so an edit and everything it cascades into undo together
+pub fn record_edit(world: &mut World) {
+    let grounds: Vec<(Entity, f32, Soil)> = world
+        .query_filtered::<(Entity, &GridCell, &Soil), With<Ground>>()
+        .iter(world)
+        .map(|(entity, cell, soil)| (entity, cell.layer, *soil))
+        .collect();
+
+    let pairs: Vec<Pair> = world.query::<&Pair>().iter(world).cloned().collect();
+    let waters = pairs
+        .iter()
+        .filter_map(|pair| {
+            let amount = world.get::<Water>(pair.water)?.amount;
+            let layer = world.get::<GridCell>(pair.ground)?.layer;
+            Some((pair.water, amount, layer))
+        })
+        .collect();
+
+    let budget = world.resource::<EditBudget>();
+    let snapshot = Snapshot {
+        grounds,
+        waters,
+        edits: budget.edits,
+        layer_changes: budget.layer_changes,
+    };
+
+    let mut history = world.resource_mut::<UndoHistory>();
+    history.steps.push(snapshot);
+    if history.steps.len() > UNDO_LIMIT {
+        history.steps.remove(0);
+    }
+}
+
+/// Puts the map back as it was before the last edit, returning false if there was none
+pub fn undo(world: &mut World) -> bool {
+    let Some(snapshot) = world.resource_mut::<UndoHistory>().steps.pop() else {
+        return false;
+    };
+
+    for (entity, layer, soil) in snapshot.grounds {
+        //  pasting may have changed the soil
+        if world
+            .get::<Soil>(entity)
+            .is_some_and(|current| *current != soil)
+        {
+            world.entity_mut(entity).insert(soil);
+        }
+
+        let Some(mut cell) = world.get_mut::<GridCell>(entity) else {
+            continue;
+        };
+        if cell.layer == layer {
+            continue;
+        }
+
+        //  animate back without pulling the neighbors, they are restored too
+        let up = layer > cell.layer;
+        cell.layer = layer;
+        world
+            .entity_mut(entity)
+            .insert(Shifting { up, cascade: false });
+    }
+
+    for (entity, amount, layer) in snapshot.waters {
+        if let Some(mut water) = world.get_mut::<Water>(entity) {
+            water.amount = amount;
+        }
+        if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+            transform.translation.y = layer + amount;
+        }
+    }
+
+    let mut budget = world.resource_mut::<EditBudget>();
+    budget.edits = snapshot.edits;
+    budget.layer_changes = snapshot.layer_changes;
+    true
+}
+
+/// Edits of an old map cannot be undone on a new one
+fn clear_history(mut event: EventReader<GenerateMap>, mut history: ResMut<UndoHistory>) {
+    if event.read().last().is_some() {
+        history.steps.clear();
+    }
+}
+
+fn undo_key(input: ActionInput, mut commands: Commands) {
+    if input.just_pressed(Action::Undo) {
+        commands.queue(|world: &mut World| {
+            let status = if undo(world) {
+                "undid the last edit"
+            } else {
+                "nothing to undo"
+            };
+            world.resource_mut::<ToolStatus>().0 = status.to_string();
+        });
+    }
+}
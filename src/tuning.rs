@@ -0,0 +1,192 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{map::GenerateMap, LaunchOptions};
+
+const TUNING_PATH: &str = "./assets/tuning.json";
+
+pub struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        //  the config is read while building, so startup systems already see it
+        let path = app
+            .world()
+            .get_resource::<LaunchOptions>()
+            .and_then(|options| options.tuning.clone())
+            .or_else(|| Some(PathBuf::from(TUNING_PATH)).filter(|path| path.exists()));
+        let config = match path {
+            Some(path) => TuningConfig::load(&path).unwrap_or_else(|e| {
+                error!("failed to load tuning from {:?}: {}", path, e);
+                TuningConfig::default()
+            }),
+            None => TuningConfig::default(),
+        };
+
+        app.insert_resource(BaseTuning(config.simulation.clone()))
+            .insert_resource(config.simulation)
+            .insert_resource(config.camera);
+    }
+}
+
+/// Simulation constants, adjustable while the game runs
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tuning {
+    /// how fast ground cells move to their new layer (per second)
+    pub shift_rate: f32,
+    /// how much water moves between cells per frame
+    pub water_speed: f32,
+    /// water level differences below this are treated as level
+    pub level_cutoff: f32,
+    /// the height of one layer, existing cells keep their heights until the map is regenerated
+    pub cell_height: f32,
+    /// the size of water cubes relative to ground, applied when the map is next generated
+    pub water_mesh_scale: f32,
+    /// water changes below this per tick count as still
+    pub settle_threshold: f32,
+    /// how many still ticks in a row before the map counts as settled
+    pub settle_ticks: u32,
+    /// how many direction changes in a row before a cell counts as oscillating
+    pub oscillation_reversals: u32,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            shift_rate: 8.4,
+            water_speed: 0.02,
+            level_cutoff: 0.05,
+            cell_height: 0.5,
+            water_mesh_scale: 0.98,
+            settle_threshold: 0.001,
+            settle_ticks: 30,
+            oscillation_reversals: 8,
+        }
+    }
+}
+
+/// The simulation tuning before any map overrides
+#[derive(Resource, Debug)]
+pub struct BaseTuning(pub Tuning);
+
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraTuning {
+    pub distance: f32,
+    pub zoom_rate: f32,
+    pub rotation_sensitivity: f32,
+    pub zoom_min: f32,
+    pub zoom_max: f32,
+    pub translate_rate: f32,
+}
+
+impl Default for CameraTuning {
+    fn default() -> Self {
+        Self {
+            distance: 24.0,
+            zoom_rate: 6.0,
+            rotation_sensitivity: 0.06,
+            zoom_min: 0.5,
+            zoom_max: 2.0,
+            translate_rate: 12.0,
+        }
+    }
+}
+
+/// Simulation values a map file replaces while it is played
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TuningOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shift_rate: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub water_speed: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level_cutoff: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cell_height: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub water_mesh_scale: Option<f32>,
+}
+
+impl TuningOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn apply(&self, tuning: &mut Tuning) {
+        let overrides = [
+            (self.shift_rate, &mut tuning.shift_rate),
+            (self.water_speed, &mut tuning.water_speed),
+            (self.level_cutoff, &mut tuning.level_cutoff),
+            (self.cell_height, &mut tuning.cell_height),
+            (self.water_mesh_scale, &mut tuning.water_mesh_scale),
+        ];
+        for (value, field) in overrides {
+            if let Some(value) = value {
+                *field = value;
+            }
+        }
+    }
+}
+
+/// The layout of the tuning file, also used to name values in the console
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TuningConfig {
+    pub simulation: Tuning,
+    pub camera: CameraTuning,
+}
+
+impl TuningConfig {
+    fn load(path: &Path) -> Result<Self, String> {
+        let json_data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&json_data).map_err(|e| e.to_string())
+    }
+
+    /// Reads a value by its dotted name, e.g. `simulation.shift_rate`
+    pub fn get(&self, key: &str) -> Option<Value> {
+        let value = serde_json::to_value(self).ok()?;
+        value.pointer(&key_pointer(key)).cloned()
+    }
+
+    /// Writes a value by its dotted name, keeping the old config if it does not fit
+    pub fn set(&mut self, key: &str, new_value: Value) -> Result<(), String> {
+        let mut value = serde_json::to_value(&*self).map_err(|e| e.to_string())?;
+        let Some(field) = value.pointer_mut(&key_pointer(key)) else {
+            return Err(format!("{:?} is not a tuning value", key));
+        };
+        if field.is_object() {
+            return Err(format!("{:?} is a group, not a value", key));
+        }
+
+        *field = new_value;
+        *self = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+fn key_pointer(key: &str) -> String {
+    format!("/{}", key.replace('.', "/"))
+}
+
+/// Starts every generated map from the base tuning and the map's own overrides
+pub fn apply_map_tuning(
+    mut event: EventReader<GenerateMap>,
+    base: Res<BaseTuning>,
+    mut tuning: ResMut<Tuning>,
+) {
+    let Some(generation) = event.read().last() else {
+        return;
+    };
+
+    let mut map_tuning = base.0.clone();
+    generation.settings.tuning.apply(&mut map_tuning);
+    *tuning = map_tuning;
+}
@@ -1,16 +1,26 @@
 use bevy::prelude::*;
 
-use crate::{grid::CELL_HEIGHT, ground::Ground, pair::Pair};
+use crate::{
+    editor::GameMode, fluid_dynamics::AddDrainingToEmptyWater, ground::Ground, pair::Pair,
+};
 
-pub const WATER_MESH_SCALE: f32 = 0.98;
 pub const WATER_COLOR: Color = Color::srgb(0.0, 0.2, 0.9);
 
 pub struct WaterPlugin;
 
 impl Plugin for WaterPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<TryShiftWater>().add_event::<ShiftWater>();
-        app.add_systems(Update, (try_shift_water, shift_water));
+        app.add_event::<TryShiftWater>()
+            .add_event::<ShiftWater>()
+            .add_event::<ManuallyIncreaseWater>();
+        app.add_systems(
+            Update,
+            (
+                try_shift_water,
+                shift_water,
+                (create_water, flow_sources, flow_sinks).run_if(in_state(GameMode::Play)),
+            ),
+        );
     }
 }
 
@@ -19,10 +29,23 @@ pub struct Water {
     pub amount: f32,
 }
 
+/// Continuously adds water to its cell
+#[derive(Component, Debug, Default)]
+pub struct WaterSource {
+    pub rate: f32,
+}
+
+/// Continuously removes water from its cell
+#[derive(Component, Debug, Default)]
+pub struct WaterSink {
+    pub rate: f32,
+}
+
 #[derive(Event)]
 pub struct TryShiftWater {
     pub ground: Entity,
-    pub shifting_upward: bool,
+    /// the height the ground finished shifting to
+    pub layer: f32,
 }
 
 fn try_shift_water(
@@ -42,7 +65,7 @@ fn try_shift_water(
             if pair.ground == ground_entity {
                 water_selected.send(ShiftWater {
                     entity: pair.water,
-                    upward: check.shifting_upward,
+                    layer: check.layer,
                 });
                 break;
             }
@@ -53,20 +76,70 @@ fn try_shift_water(
 #[derive(Event, Debug)]
 pub struct ShiftWater {
     pub entity: Entity,
-    pub upward: bool,
+    pub layer: f32,
 }
 
 fn shift_water(
     mut event: EventReader<ShiftWater>,
-    mut waters: Query<&mut Transform, Without<Ground>>,
+    mut waters: Query<(&Water, &mut Transform), Without<Ground>>,
 ) {
     for shift in event.read() {
-        let Ok(mut transform) = waters.get_mut(shift.entity) else {
+        let Ok((water, mut transform)) = waters.get_mut(shift.entity) else {
             continue;
         };
 
-        //  update found water
-        let direction = if shift.upward { 1.0 } else { -1.0 };
-        transform.translation.y += direction * CELL_HEIGHT;
+        //  the water surface rests on the ground, however far it moved
+        transform.translation.y = shift.layer + water.amount;
+    }
+}
+
+#[derive(Event)]
+pub struct ManuallyIncreaseWater {
+    pub ground: Entity,
+    /// may be negative to remove water, but never below empty
+    pub amount: f32,
+}
+
+fn create_water(
+    mut event: EventReader<ManuallyIncreaseWater>,
+    pairs: Query<&Pair>,
+    mut waters: Query<(Entity, &mut Water, &mut Transform)>,
+    mut add_draining: EventWriter<AddDrainingToEmptyWater>,
+) {
+    for check in event.read() {
+        //  find current water, if exists
+        for pair in pairs.iter() {
+            if pair.ground == check.ground {
+                //  increase the water amount and attach drainable to it
+                if let Ok((water_entity, mut water, mut transform)) = waters.get_mut(pair.water) {
+                    let amount = check.amount.max(-water.amount);
+                    water.amount += amount;
+                    transform.translation.y += amount;
+
+                    add_draining.send(AddDrainingToEmptyWater {
+                        water: water_entity,
+                    });
+                };
+
+                break;
+            }
+        }
+    }
+}
+
+fn flow_sources(time: Res<Time>, mut sources: Query<(&WaterSource, &mut Water, &mut Transform)>) {
+    for (source, mut water, mut transform) in sources.iter_mut() {
+        let flow_amount = source.rate * time.delta_secs();
+        water.amount += flow_amount;
+        transform.translation.y += flow_amount;
+    }
+}
+
+fn flow_sinks(time: Res<Time>, mut sinks: Query<(&WaterSink, &mut Water, &mut Transform)>) {
+    for (sink, mut water, mut transform) in sinks.iter_mut() {
+        //  a sink cannot take more water than there is
+        let flow_amount = (sink.rate * time.delta_secs()).min(water.amount.max(0.0));
+        water.amount -= flow_amount;
+        transform.translation.y -= flow_amount;
     }
 }